@@ -0,0 +1,412 @@
+//! Directed graphs over dense node ids, the usual searches on them and cycle finding for
+//! functional graphs given as a step function.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Interning table mapping arbitrary node names to dense ids `0..len` and back.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// Returns the id of `name`, assigning the next free one if it is new.
+    pub fn intern(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    /// All names, indexed by id.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Directed graph over the nodes `0..len` with weighted edges kept in insertion order.
+///
+/// Unweighted edges have weight 1; parallel edges and self-loops are allowed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    adjacency: Vec<Vec<(usize, u64)>>,
+}
+
+impl Graph {
+    /// A graph of `len` nodes without edges.
+    pub fn with_len(len: usize) -> Self {
+        Graph { adjacency: vec![Vec::new(); len] }
+    }
+
+    /// Adds a node without edges and returns its id.
+    pub fn add_node(&mut self) -> usize {
+        self.adjacency.push(Vec::new());
+        self.adjacency.len() - 1
+    }
+
+    /// Adds an edge of weight 1, growing the graph to contain both ends.
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    /// Adds an edge of weight `weight`, growing the graph to contain both ends.
+    pub fn add_weighted_edge(&mut self, from: usize, to: usize, weight: u64) {
+        let len = self.adjacency.len().max(from + 1).max(to + 1);
+        self.adjacency.resize(len, Vec::new());
+        self.adjacency[from].push((to, weight));
+    }
+
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    /// Outgoing edges of `node` as `(target, weight)`, in the order they were added.
+    pub fn edges(&self, node: usize) -> &[(usize, u64)] {
+        &self.adjacency[node]
+    }
+
+    /// Targets of the outgoing edges of `node`, in the order they were added.
+    pub fn successors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adjacency[node].iter().map(|&(target, _)| target)
+    }
+
+    /// Number of edges on the shortest path from `start` to every node, `None` where unreachable.
+    pub fn bfs(&self, start: usize) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.len()];
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[node].map(|d| d + 1);
+            for next in self.successors(node) {
+                if distances[next].is_none() {
+                    distances[next] = distance;
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    /// A path with the fewest edges from `start` to the nearest node accepted by `goal`, both
+    /// included.
+    pub fn shortest_path(&self, start: usize, goal: impl Fn(usize) -> bool) -> Option<Vec<usize>> {
+        let mut parents: Vec<Option<usize>> = vec![None; self.len()];
+        let mut queue = VecDeque::from([start]);
+        parents[start] = Some(start);
+        while let Some(node) = queue.pop_front() {
+            if goal(node) {
+                return Some(trace(&parents, node));
+            }
+            for next in self.successors(node) {
+                if parents[next].is_none() {
+                    parents[next] = Some(node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Nodes reachable from `start` in depth-first preorder, following edges in insertion order.
+    pub fn dfs(&self, start: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut visited[node], true) {
+                continue;
+            }
+            order.push(node);
+            // pushed in reverse so that the first edge is explored first
+            let successors: Vec<usize> = self.successors(node).collect();
+            stack.extend(successors.into_iter().rev().filter(|&next| !visited[next]));
+        }
+        order
+    }
+
+    /// Weight of the lightest path from `start` to every node, `None` where unreachable.
+    pub fn dijkstra(&self, start: usize) -> Vec<Option<u64>> {
+        let mut distances = vec![None; self.len()];
+        let mut heap = BinaryHeap::from([Reverse((0, start))]);
+        while let Some(Reverse((distance, node))) = heap.pop() {
+            if distances[node].is_some() {
+                continue;
+            }
+            distances[node] = Some(distance);
+            for &(next, weight) in self.edges(node) {
+                if distances[next].is_none() {
+                    heap.push(Reverse((distance + weight, next)));
+                }
+            }
+        }
+        distances
+    }
+
+    /// The lightest path from `start` to a node accepted by `goal` and its weight.
+    ///
+    /// `heuristic` has to be consistent, i.e. never overestimate the remaining weight and never
+    /// drop by more than the weight of an edge; `|_| 0` turns this into Dijkstra.
+    pub fn a_star(&self, start: usize, goal: impl Fn(usize) -> bool, heuristic: impl Fn(usize) -> u64)
+        -> Option<(u64, Vec<usize>)> {
+        let mut best: Vec<Option<u64>> = vec![None; self.len()];
+        let mut parents: Vec<Option<usize>> = vec![None; self.len()];
+        let mut done = vec![false; self.len()];
+        best[start] = Some(0);
+        parents[start] = Some(start);
+        let mut heap = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+        while let Some(Reverse((_, distance, node))) = heap.pop() {
+            if std::mem::replace(&mut done[node], true) {
+                continue;
+            }
+            if goal(node) {
+                return Some((distance, trace(&parents, node)));
+            }
+            for &(next, weight) in self.edges(node) {
+                let candidate = distance + weight;
+                if !done[next] && best[next].is_none_or(|known| candidate < known) {
+                    best[next] = Some(candidate);
+                    parents[next] = Some(node);
+                    heap.push(Reverse((candidate + heuristic(next), candidate, next)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Strongly connected components (Tarjan), each listed once, in reverse topological order:
+    /// no component has an edge into a later one.
+    pub fn sccs(&self) -> Vec<Vec<usize>> {
+        let mut index: Vec<Option<usize>> = vec![None; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..self.len() {
+            if index[root].is_some() {
+                continue;
+            }
+            // explicit call stack of (node, next edge to look at) to survive deep graphs
+            let mut calls = vec![(root, 0)];
+            index[root] = Some(counter);
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&(node, edge)) = calls.last() {
+                if let Some(&(next, _)) = self.adjacency[node].get(edge) {
+                    calls.last_mut().unwrap().1 += 1;
+                    match index[next] {
+                        None => {
+                            index[next] = Some(counter);
+                            low[next] = counter;
+                            counter += 1;
+                            stack.push(next);
+                            on_stack[next] = true;
+                            calls.push((next, 0));
+                        },
+                        Some(next_index) if on_stack[next] => low[node] = low[node].min(next_index),
+                        Some(_) => {},
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if index[node] == Some(low[node]) {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+}
+
+/// Follows `parents` back from `node` to the node that is its own parent.
+fn trace(parents: &[Option<usize>], mut node: usize) -> Vec<usize> {
+    let mut path = vec![node];
+    while let Some(parent) = parents[node].filter(|&parent| parent != node) {
+        path.push(parent);
+        node = parent;
+    }
+    path.reverse();
+    path
+}
+
+/// The rho-shaped walk through a functional graph: a tail leading into a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rho<S> {
+    /// Every state in the order visited, up to but excluding the first repeated one.
+    pub states: Vec<S>,
+    /// Index in `states` at which the cycle starts, i.e. the length of the tail.
+    pub prefix: usize,
+}
+
+impl<S> Rho<S> {
+    /// Number of states on the cycle.
+    pub fn length(&self) -> usize {
+        self.states.len() - self.prefix
+    }
+}
+
+/// Applies `next` from `start` until a state repeats; every state must have exactly one
+/// successor and the reachable states must be finite.
+pub fn find_cycle<S: Clone + Eq + Hash>(start: S, mut next: impl FnMut(&S) -> S) -> Rho<S> {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = start;
+    loop {
+        if let Some(&prefix) = seen.get(&state) {
+            return Rho { states, prefix };
+        }
+        seen.insert(state.clone(), states.len());
+        let following = next(&state);
+        states.push(state);
+        state = following;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 -4-> 1, 0 -1-> 2, 2 -2-> 1, 1 -1-> 3, 2 -5-> 3, and 4 unreachable from 0.
+    fn weighted() -> Graph {
+        let mut graph = Graph::with_len(5);
+        for (from, to, weight) in [(0, 1, 4), (0, 2, 1), (2, 1, 2), (1, 3, 1), (2, 3, 5), (4, 0, 1)] {
+            graph.add_weighted_edge(from, to, weight);
+        }
+        graph
+    }
+
+    #[test]
+    fn interning_names() {
+        let mut nodes = Interner::new();
+        assert_eq!((nodes.intern("AAA"), nodes.intern("BBB"), nodes.intern("AAA")), (0, 1, 0));
+        assert_eq!((nodes.id("BBB"), nodes.id("CCC")), (Some(1), None));
+        assert_eq!(nodes.name(1), "BBB");
+        assert_eq!(nodes.names(), ["AAA", "BBB"]);
+    }
+
+    #[test]
+    fn edges_grow_the_graph() {
+        let mut graph = Graph::default();
+        graph.add_edge(2, 0);
+        graph.add_edge(2, 2);
+        graph.add_edge(2, 0);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edges(2), [(0, 1), (2, 1), (0, 1)]);
+        assert_eq!(graph.successors(1).count(), 0);
+        assert_eq!(graph.add_node(), 3);
+    }
+
+    #[test]
+    fn breadth_first_counts_edges() {
+        let graph = weighted();
+        assert_eq!(graph.bfs(0), [Some(0), Some(1), Some(1), Some(2), None]);
+        assert_eq!(graph.shortest_path(0, |node| node == 3), Some(vec![0, 1, 3]));
+        assert_eq!(graph.shortest_path(0, |node| node == 0), Some(vec![0]));
+        assert_eq!(graph.shortest_path(0, |node| node == 4), None);
+    }
+
+    #[test]
+    fn depth_first_follows_edges_in_order() {
+        let graph = weighted();
+        assert_eq!(graph.dfs(0), [0, 1, 3, 2]);
+        assert_eq!(graph.dfs(4), [4, 0, 1, 3, 2]);
+        assert_eq!(graph.dfs(3), [3]);
+    }
+
+    #[test]
+    fn dijkstra_takes_the_lightest_path() {
+        let graph = weighted();
+        assert_eq!(graph.dijkstra(0), [Some(0), Some(3), Some(1), Some(4), None]);
+        assert_eq!(graph.dijkstra(3), [None, None, None, Some(0), None]);
+    }
+
+    #[test]
+    fn a_star_with_and_without_heuristic() {
+        let graph = weighted();
+        assert_eq!(graph.a_star(0, |node| node == 3, |_| 0), Some((4, vec![0, 2, 1, 3])));
+        // the exact remaining weight is the most informed consistent heuristic
+        let remaining = [4, 1, 3, 0, 5];
+        assert_eq!(graph.a_star(0, |node| node == 3, |node| remaining[node]), Some((4, vec![0, 2, 1, 3])));
+        assert_eq!(graph.a_star(4, |node| node == 1, |_| 0), Some((4, vec![4, 0, 2, 1])));
+        assert_eq!(graph.a_star(0, |node| node == 4, |_| 0), None);
+    }
+
+    #[test]
+    fn components_in_reverse_topological_order() {
+        let mut graph = Graph::with_len(6);
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (5, 5)] {
+            graph.add_edge(from, to);
+        }
+        let components: Vec<Vec<usize>> = graph.sccs().into_iter()
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        assert_eq!(components, [vec![3, 4], vec![0, 1, 2], vec![5]]);
+
+        let chain: Vec<Vec<usize>> = weighted().sccs();
+        assert_eq!(chain, [vec![3], vec![1], vec![2], vec![0], vec![4]]);
+    }
+
+    #[test]
+    fn cycle_after_a_tail() {
+        let rho = find_cycle(0, |&n| if n < 3 { n + 1 } else { n - 2 });
+        assert_eq!(rho, Rho { states: vec![0, 1, 2, 3], prefix: 1 });
+        assert_eq!(rho.length(), 3);
+    }
+
+    #[test]
+    fn cycle_through_the_start() {
+        let rho = find_cycle(0, |&n| (n + 1) % 4);
+        assert_eq!((rho.states.len(), rho.prefix, rho.length()), (4, 0, 4));
+        let fixed = find_cycle("x", |_| "x");
+        assert_eq!((fixed.states, fixed.prefix), (vec!["x"], 0));
+    }
+}
@@ -0,0 +1,257 @@
+//! Rectangular 2D grids addressed by [`Pos`].
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// Cell coordinate; `x` grows to the right, `y` grows downwards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Pos {
+    pub fn new(x: usize, y: usize) -> Self {
+        Pos { x, y }
+    }
+
+    /// Returns the position shifted by `(dx, dy)`, or `None` if it would leave the first quadrant.
+    pub fn offset(self, dx: isize, dy: isize) -> Option<Pos> {
+        Some(Pos {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Offsets of the four orthogonal neighbours.
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets of the eight orthogonal and diagonal neighbours.
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0), (1, 0),
+    (-1, 1), (0, 1), (1, 1),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    /// Row `row` has `found` cells while the first row has `expected`.
+    Ragged { row: usize, expected: usize, found: usize },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid is empty"),
+            GridError::Ragged { row, expected, found } =>
+                write!(f, "row {row} has {found} cells, expected {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// Rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, which must all be non-empty and of the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = rows.first().map(Vec::len).unwrap_or(0);
+        if width == 0 {
+            return Err(GridError::Empty);
+        }
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (row, cols) in rows.into_iter().enumerate() {
+            if cols.len() != width {
+                return Err(GridError::Ragged { row, expected: width, found: cols.len() });
+            }
+            cells.extend(cols);
+        }
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos.y * self.width + pos.x])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        match self.contains(pos) {
+            true => Some(&mut self.cells[pos.y * self.width + pos.x]),
+            false => None,
+        }
+    }
+
+    /// Iterates over all positions row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Pos { x, y }))
+    }
+
+    /// Iterates over all cells together with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Iterates over the in-bounds positions at the given offsets from `pos`.
+    pub fn neighbours<'a>(&self, pos: Pos, offsets: &'a [(isize, isize)]) -> impl Iterator<Item = Pos> + 'a {
+        let (width, height) = (self.width, self.height);
+        offsets.iter()
+            .filter_map(move |&(dx, dy)| pos.offset(dx, dy))
+            .filter(move |n| n.x < width && n.y < height)
+    }
+
+    /// Iterates over the up to four orthogonal neighbours of `pos`.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbours(pos, &NEIGHBOURS_4)
+    }
+
+    /// Iterates over the up to eight orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        self.neighbours(pos, &NEIGHBOURS_8)
+    }
+
+    /// The cells of row `y`, left to right; panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {y} is outside the {}x{} grid", self.width, self.height);
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Iterates over the cells of column `x`, top to bottom; panics if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside the {}x{} grid", self.width, self.height);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{pos} is outside the {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| panic!("{pos} is outside the {width}x{height} grid"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::from_rows(s
+            .lines()
+            .map(|row| row.chars().collect())
+            .collect())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef\n".parse().unwrap()
+    }
+
+    #[test]
+    fn parsing_rejects_empty_and_ragged_grids() {
+        assert_eq!("".parse::<Grid<char>>(), Err(GridError::Empty));
+        assert_eq!("\nabc".parse::<Grid<char>>(), Err(GridError::Empty));
+        assert_eq!("abc\nab\nabc".parse::<Grid<char>>(), Err(GridError::Ragged { row: 1, expected: 3, found: 2 }));
+        assert_eq!("äb\ncd".parse::<Grid<char>>().map(|grid| grid.width()), Ok(2));
+    }
+
+    #[test]
+    fn cells_by_position() {
+        let mut grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!((grid[Pos::new(2, 1)], grid.get(Pos::new(3, 0)), grid.get(Pos::new(0, 2))), ('f', None, None));
+        grid[Pos::new(0, 1)] = 'x';
+        assert_eq!(grid.to_string(), "abc\nxef\n");
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = grid();
+        let corner: Vec<Pos> = grid.neighbours8(Pos::new(0, 0)).collect();
+        assert_eq!(corner, [Pos::new(1, 0), Pos::new(0, 1), Pos::new(1, 1)]);
+        let corner: Vec<Pos> = grid.neighbours8(Pos::new(2, 1)).collect();
+        assert_eq!(corner, [Pos::new(1, 0), Pos::new(2, 0), Pos::new(1, 1)]);
+        assert_eq!(grid.neighbours8(Pos::new(1, 0)).count(), 5);
+        assert_eq!(grid.neighbours4(Pos::new(0, 1)).count(), 2);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = grid();
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        let columns: Vec<String> = grid.columns().map(|column| column.collect()).collect();
+        assert_eq!(columns, ["ad", "be", "cf"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "row 2 is outside the 3x2 grid")]
+    fn row_out_of_bounds() {
+        grid().row(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside the 3x2 grid")]
+    fn column_out_of_bounds() {
+        let _ = grid().column(3);
+    }
+}
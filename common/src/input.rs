@@ -0,0 +1,125 @@
+//! Loading puzzle inputs independently of how they were saved.
+//!
+//! Inputs may come with Windows (`\r\n`) or old Mac (`\r`) line endings, trailing spaces or tabs,
+//! leading or trailing blank lines and with or without a final newline. [`normalize`] turns all of
+//! them into the same text: lines separated by `\n`, no trailing whitespace on any line, no blank
+//! lines at either end and exactly one final newline.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Reads the file at `path` and [`normalize`]s it.
+pub fn read(path: impl AsRef<Path>) -> io::Result<String> {
+    fs::read_to_string(path).map(|raw| normalize(&raw))
+}
+
+/// Unifies line endings, strips trailing whitespace from every line and trims blank lines from
+/// both ends, terminating the result with a single newline unless it is empty.
+pub fn normalize(raw: &str) -> String {
+    let unified = raw.replace("\r\n", "\n").replace('\r', "\n");
+    let lines: Vec<&str> = unified
+        .split('\n')
+        .map(str::trim_end)
+        .skip_while(|line| line.is_empty())
+        .collect();
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |last| last + 1);
+
+    let mut text = lines[..end].join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text
+}
+
+/// Splits `text` into sections separated by one or more blank lines.
+///
+/// Lines holding only whitespace count as blank, so separators are also found in text that was
+/// not [`normalize`]d. Sections keep their inner line breaks but lose the newline before the
+/// separator.
+pub fn sections(text: &str) -> Vec<&str> {
+    let mut sections: Vec<&str> = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            if let Some(begin) = start.take() {
+                sections.push(&text[begin..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    if let Some(begin) = start {
+        sections.push(&text[begin..end]);
+    }
+    sections
+}
+
+/// Yields the lines of `reader` one at a time with line endings and trailing whitespace
+/// stripped, for inputs too large to [`read`] at once.
+pub fn lines(reader: impl io::BufRead) -> impl Iterator<Item = io::Result<String>> {
+    reader.lines().map(|line| line.map(|line| line.trim_end().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_unifies_line_endings() {
+        assert_eq!(normalize("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(normalize("a\rb\r"), "a\nb\n");
+        assert_eq!(normalize("a\r\n\r\nb\rc\n"), "a\n\nb\nc\n");
+    }
+
+    #[test]
+    fn normalize_strips_trailing_whitespace_only() {
+        assert_eq!(normalize("a \t\n  b\t\t\n"), "a\n  b\n");
+    }
+
+    #[test]
+    fn normalize_trims_blank_lines_at_both_ends() {
+        assert_eq!(normalize("\n\n  \na\n\nb\n\n\t\n"), "a\n\nb\n");
+        assert_eq!(normalize("\n \n\t"), "");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn normalize_adds_a_missing_final_newline() {
+        assert_eq!(normalize("a\nb"), "a\nb\n");
+        assert_eq!(normalize(&normalize("a\nb")), "a\nb\n");
+    }
+
+    #[test]
+    fn sections_split_on_blank_lines() {
+        assert_eq!(sections("a\nb\n\nc\n"), ["a\nb", "c"]);
+        assert_eq!(sections("\n\na\n\n\n\nb\n\n"), ["a", "b"]);
+        assert_eq!(sections("a\nb"), ["a\nb"]);
+        assert!(sections("").is_empty() && sections("\n \n").is_empty());
+    }
+
+    #[test]
+    fn sections_treat_whitespace_lines_as_separators() {
+        assert_eq!(sections("a\n \t\nb\n"), ["a", "b"]);
+        assert_eq!(sections("a\r\n\r\nb  \r\n"), ["a", "b  "]);
+    }
+
+    #[test]
+    fn sections_are_subslices_of_the_text() {
+        let text = "x\n\n  y\nz\n";
+        let parts = sections(text);
+        assert_eq!(parts, ["x", "  y\nz"]);
+        assert_eq!(parts[1].as_ptr() as usize - text.as_ptr() as usize, 3);
+    }
+
+    #[test]
+    fn streamed_lines_are_stripped() {
+        let lines: Vec<String> = lines(io::Cursor::new("a \r\nb\t\n\nc")).collect::<io::Result<_>>().unwrap();
+        assert_eq!(lines, ["a", "b", "", "c"]);
+    }
+}
@@ -0,0 +1,350 @@
+//! Half-open integer intervals, sets of them and piecewise translations between them.
+
+use std::fmt;
+
+/// The integers `start..end`; empty when `start >= end`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        Interval { start, end }
+    }
+
+    /// The `len` integers starting at `start`.
+    pub fn with_len(start: i64, len: i64) -> Self {
+        Interval { start, end: start + len }
+    }
+
+    /// Number of integers in the interval.
+    pub fn len(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The integers in both intervals, possibly empty.
+    pub fn intersection(&self, other: &Interval) -> Interval {
+        Interval { start: self.start.max(other.start), end: self.end.min(other.end) }
+    }
+
+    /// The interval moved by `offset`.
+    pub fn shift(&self, offset: i64) -> Interval {
+        Interval { start: self.start + offset, end: self.end + offset }
+    }
+
+    /// Splits into the parts below and from `point` on; either may be empty.
+    pub fn split_at(&self, point: i64) -> (Interval, Interval) {
+        let point = point.clamp(self.start, self.end.max(self.start));
+        (Interval { start: self.start, end: point }, Interval { start: point, end: self.end })
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Any set of integers, stored as sorted, disjoint and non-adjacent [`Interval`]s.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// Adds all integers of `interval`, merging it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|other| other.end < interval.start);
+        let last = self.intervals.partition_point(|other| other.start <= interval.end);
+        let merged = self.intervals[first..last].iter()
+            .fold(interval, |merged, other| Interval {
+                start: merged.start.min(other.start),
+                end: merged.end.max(other.end),
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// The intervals in ascending order.
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> i64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end <= value);
+        self.intervals.get(index).is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.intervals.last().map(|interval| interval.end - 1)
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals.iter().chain(other.intervals.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            let both = a.intersection(b);
+            if !both.is_empty() {
+                intervals.push(both);
+            }
+            match a.end <= b.end {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The integers of `self` that are not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let mut skipped = 0;
+        for interval in self.intervals.iter() {
+            skipped += other.intervals[skipped..].partition_point(|o| o.end <= interval.start);
+            let mut start = interval.start;
+            for removed in other.intervals[skipped..].iter().take_while(|o| o.start < interval.end) {
+                if removed.start > start {
+                    intervals.push(Interval::new(start, removed.start));
+                }
+                start = removed.end;
+            }
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The set moved by `offset`.
+    pub fn shift(&self, offset: i64) -> IntervalSet {
+        IntervalSet { intervals: self.intervals.iter().map(|interval| interval.shift(offset)).collect() }
+    }
+
+    /// Cuts the intervals at every boundary lying strictly inside one of them.
+    pub fn split(&self, boundaries: impl IntoIterator<Item = i64>) -> Vec<Interval> {
+        let mut boundaries: Vec<i64> = boundaries.into_iter().collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let mut pieces = Vec::new();
+        for &interval in self.intervals.iter() {
+            let first = boundaries.partition_point(|&point| point <= interval.start);
+            let mut rest = interval;
+            for &point in boundaries[first..].iter().take_while(|&&point| point < interval.end) {
+                let (piece, tail) = rest.split_at(point);
+                pieces.push(piece);
+                rest = tail;
+            }
+            pieces.push(rest);
+        }
+        pieces
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(interval);
+        set
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let intervals: Vec<String> = self.intervals.iter().map(Interval::to_string).collect();
+        write!(f, "{{{}}}", intervals.join(", "))
+    }
+}
+
+/// Piecewise translation: values in the source interval of a piece move by its offset, all other
+/// values map to themselves. Where sources overlap, the piece added first wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mapping {
+    pieces: Vec<(Interval, i64)>,
+}
+
+impl Mapping {
+    pub fn new() -> Self {
+        Mapping::default()
+    }
+
+    /// Moves the values of `source` by `offset`, unless an earlier piece already covers them.
+    pub fn push(&mut self, source: Interval, offset: i64) {
+        self.pieces.push((source, offset));
+    }
+
+    /// The source intervals and their offsets, in the order they were added.
+    pub fn pieces(&self) -> &[(Interval, i64)] {
+        &self.pieces
+    }
+
+    pub fn map(&self, value: i64) -> i64 {
+        self.pieces.iter()
+            .find(|(source, _)| source.contains(value))
+            .map_or(value, |(_, offset)| value + offset)
+    }
+
+    /// Maps every value of `set` at once.
+    pub fn map_set(&self, set: &IntervalSet) -> IntervalSet {
+        let mut mapped = IntervalSet::new();
+        let mut unmapped = set.clone();
+        for &(source, offset) in self.pieces.iter() {
+            let source = IntervalSet::from(source);
+            for interval in unmapped.intersection(&source).intervals() {
+                mapped.insert(interval.shift(offset));
+            }
+            unmapped = unmapped.difference(&source);
+        }
+        mapped.union(&unmapped)
+    }
+}
+
+impl FromIterator<(Interval, i64)> for Mapping {
+    fn from_iter<I: IntoIterator<Item = (Interval, i64)>>(iter: I) -> Self {
+        Mapping { pieces: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    fn bounds(set: &IntervalSet) -> Vec<(i64, i64)> {
+        set.intervals().iter().map(|interval| (interval.start, interval.end)).collect()
+    }
+
+    #[test]
+    fn splitting_an_interval() {
+        let interval = Interval::new(0, 10);
+        assert_eq!(interval.split_at(4), (Interval::new(0, 4), Interval::new(4, 10)));
+        assert_eq!(interval.split_at(-5), (Interval::new(0, 0), interval));
+        assert_eq!(interval.split_at(15), (interval, Interval::new(10, 10)));
+        assert!(interval.intersection(&Interval::new(10, 20)).is_empty());
+        assert_eq!(Interval::new(5, 3).len(), 0);
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_touching_intervals() {
+        let mut intervals = set(&[(0, 3)]);
+        intervals.insert(Interval::new(3, 5));
+        assert_eq!(bounds(&intervals), [(0, 5)]);
+        intervals.insert(Interval::new(7, 9));
+        intervals.insert(Interval::new(-4, -2));
+        assert_eq!(bounds(&intervals), [(-4, -2), (0, 5), (7, 9)]);
+        intervals.insert(Interval::new(5, 7));
+        assert_eq!(bounds(&intervals), [(-4, -2), (0, 9)]);
+        intervals.insert(Interval::new(8, 1));
+        assert_eq!(bounds(&intervals), [(-4, -2), (0, 9)]);
+        assert_eq!(bounds(&set(&[(8, 10), (0, 2), (4, 6), (1, 9)])), [(0, 10)]);
+    }
+
+    #[test]
+    fn membership_and_extent() {
+        let intervals = set(&[(0, 5), (10, 15)]);
+        assert!(intervals.contains(0) && intervals.contains(14));
+        assert!(!intervals.contains(5) && !intervals.contains(-1) && !intervals.contains(15));
+        assert_eq!((intervals.min(), intervals.max(), intervals.len()), (Some(0), Some(14), 10));
+        assert_eq!((IntervalSet::new().min(), IntervalSet::new().len()), (None, 0));
+    }
+
+    #[test]
+    fn intersection() {
+        let intervals = set(&[(0, 5), (10, 15)]);
+        assert_eq!(bounds(&intervals.intersection(&set(&[(3, 12)]))), [(3, 5), (10, 12)]);
+        assert_eq!(bounds(&intervals.intersection(&set(&[(-3, 1), (4, 11), (14, 20)]))),
+                   [(0, 1), (4, 5), (10, 11), (14, 15)]);
+        assert!(intervals.intersection(&set(&[(5, 10)])).is_empty());
+        assert!(intervals.intersection(&IntervalSet::new()).is_empty());
+    }
+
+    #[test]
+    fn difference() {
+        let whole = set(&[(0, 10)]);
+        assert_eq!(bounds(&whole.difference(&set(&[(2, 3), (5, 7), (9, 12)]))), [(0, 2), (3, 5), (7, 9)]);
+        assert_eq!(whole.difference(&IntervalSet::new()), whole);
+        assert!(whole.difference(&set(&[(-1, 11)])).is_empty());
+
+        let intervals = set(&[(0, 5), (10, 15)]);
+        assert_eq!(bounds(&intervals.difference(&set(&[(4, 11)]))), [(0, 4), (11, 15)]);
+        assert_eq!(intervals.difference(&set(&[(-5, 0), (5, 10), (15, 20)])), intervals);
+    }
+
+    #[test]
+    fn split_cuts_only_inside_intervals() {
+        let intervals = set(&[(0, 10), (20, 30)]);
+        assert_eq!(intervals.split([25, 5, 0, 20, 25, 40, 10]), [
+            Interval::new(0, 5), Interval::new(5, 10), Interval::new(20, 25), Interval::new(25, 30),
+        ]);
+        assert_eq!(intervals.split([]), intervals.intervals());
+    }
+
+    #[test]
+    fn mapping_with_overlapping_pieces() {
+        let mapping: Mapping = [(Interval::new(0, 10), 100), (Interval::new(5, 15), 1000)].into_iter().collect();
+        assert_eq!((mapping.map(7), mapping.map(12), mapping.map(20), mapping.map(-1)), (107, 1012, 20, -1));
+        assert_eq!(bounds(&mapping.map_set(&set(&[(-5, 20)]))), [(-5, 0), (15, 20), (100, 110), (1010, 1015)]);
+        for value in -5..20 {
+            assert!(mapping.map_set(&set(&[(-5, 20)])).contains(mapping.map(value)));
+        }
+    }
+
+    #[test]
+    fn mapping_merges_pieces_landing_next_to_each_other() {
+        let mut mapping = Mapping::new();
+        mapping.push(Interval::new(0, 5), 5);
+        mapping.push(Interval::new(20, 25), -10);
+        assert_eq!(bounds(&mapping.map_set(&set(&[(0, 25)]))), [(5, 20)]);
+        assert_eq!(mapping.map_set(&IntervalSet::new()), IntervalSet::new());
+    }
+}
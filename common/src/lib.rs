@@ -0,0 +1,10 @@
+//! Helpers shared between the days.
+
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod log;
+pub mod math;
+pub mod parse;
+pub mod report;
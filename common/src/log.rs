@@ -0,0 +1,85 @@
+//! Diagnostics on stderr, filtered by a process-wide verbosity.
+//!
+//! The [`warn!`](crate::warn), [`info!`](crate::info) and [`debug!`](crate::debug) macros take
+//! `format!` arguments and print a line only if the verbosity allows it. Mains pick the level
+//! with [`from_args`]: warnings are shown by default, `--quiet` hides them, `-v` adds info and
+//! `-vv` (or `-v -v`) adds debug traces. Answers are never affected.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Quiet,
+    Warn,
+    Info,
+    Debug,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Warn,
+        2 => Level::Info,
+        _ => Level::Debug,
+    }
+}
+
+/// Whether messages of `level` are currently printed.
+pub fn enabled(level: Level) -> bool {
+    level != Level::Quiet && level <= self::level()
+}
+
+/// Takes `-v`, `-vv` and `--quiet` out of `args` and sets the level accordingly; `--quiet`
+/// wins over any number of `-v`.
+pub fn from_args(args: &mut Vec<String>) {
+    let mut quiet = false;
+    let mut verbose = 0;
+    args.retain(|arg| {
+        match arg.as_str() {
+            "--quiet" => quiet = true,
+            "-v" => verbose += 1,
+            "-vv" => verbose += 2,
+            _ => return true,
+        }
+        false
+    });
+    set_level(match (quiet, verbose) {
+        (true, _) => Level::Quiet,
+        (false, 0) => Level::Warn,
+        (false, 1) => Level::Info,
+        (false, _) => Level::Debug,
+    });
+}
+
+/// Prints `message` to stderr if `level` is enabled; use the macros instead.
+pub fn log(level: Level, message: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+    match level {
+        Level::Warn => eprintln!("warning: {message}"),
+        _ => eprintln!("{message}"),
+    }
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Warn, format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Info, format_args!($($arg)*)) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log($crate::log::Level::Debug, format_args!($($arg)*)) };
+}
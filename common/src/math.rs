@@ -0,0 +1,300 @@
+//! Number theory on primitive integers: gcd and lcm, modular arithmetic, Chinese remainders and
+//! integer square roots.
+//!
+//! Nothing here overflows silently: results that do not fit the integer type are `None`.
+
+use std::ops::{Div, Rem};
+
+/// The primitive integer types [`gcd`] and [`lcm`] work on.
+pub trait Integer: Copy + Ord + Div<Output = Self> + Rem<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    (signed: $($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> { <$t>::checked_mul(self, rhs) }
+            fn checked_rem(self, rhs: Self) -> Option<Self> { <$t>::checked_rem(self, rhs) }
+            fn checked_abs(self) -> Option<Self> { <$t>::checked_abs(self) }
+        }
+    )*};
+    (unsigned: $($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> { <$t>::checked_mul(self, rhs) }
+            fn checked_rem(self, rhs: Self) -> Option<Self> { <$t>::checked_rem(self, rhs) }
+            fn checked_abs(self) -> Option<Self> { Some(self) }
+        }
+    )*};
+}
+
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+impl_integer!(unsigned: u8, u16, u32, u64, u128, usize);
+
+/// Greatest common divisor, always non-negative; `gcd(0, 0) == 0`.
+///
+/// Only `None` for signed types when the result is `MIN.abs()`, e.g. `gcd(i64::MIN, 0)`.
+pub fn gcd<T: Integer>(mut a: T, mut b: T) -> Option<T> {
+    while b != T::ZERO {
+        // `MIN % -1` is the only remainder that overflows, and it is 0
+        (a, b) = (b, a.checked_rem(b).unwrap_or(T::ZERO));
+    }
+    a.checked_abs()
+}
+
+/// Least common multiple, always non-negative; 0 if either argument is 0.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+/// [`gcd`] of all values, 0 for none.
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ZERO, gcd)
+}
+
+/// [`lcm`] of all values, 1 for none.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, lcm)
+}
+
+/// Extended Euclid: returns `(g, x, y)` with `a * x + b * y == g == ±gcd(a, b)`.
+///
+/// The sign of `g` follows the remainders, so it is negative for some negative inputs. Inputs
+/// must not be `i128::MIN`.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// `(a + b) % m` for `a, b < m`, without overflowing.
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    match a >= m - b {
+        true => a - (m - b),
+        false => a + b,
+    }
+}
+
+/// `(a - b) % m` for `a, b < m`, without underflowing.
+fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    match a >= b {
+        true => a - b,
+        false => m - (b - a),
+    }
+}
+
+/// `(a * b) % m` without overflowing; `m` must be positive.
+pub fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (mut a, mut b) = (a % m, b % m);
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    product
+}
+
+/// The `x` in `0..m` with `a * x == 1 (mod m)`, if `a` and `m` are coprime and `m` is positive.
+pub fn mod_inverse(a: u128, m: u128) -> Option<u128> {
+    if m == 0 {
+        return None;
+    }
+    // extended Euclid keeping only the coefficient of `a`, reduced mod `m`
+    let (mut old_r, mut r) = (a % m, m);
+    let (mut old_s, mut s) = (1 % m, 0);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, sub_mod(old_s, mul_mod(q, s, m), m));
+    }
+    (old_r == 1).then_some(old_s)
+}
+
+/// Merges `x == a (mod m)` and `x == b (mod n)` into the single congruence `x == c (mod lcm(m, n))`
+/// and returns `(c, lcm(m, n))` with `c < lcm(m, n)`.
+///
+/// The moduli need not be coprime. `None` if they are 0, the congruences contradict each other
+/// or the lcm does not fit in a `u128`.
+pub fn crt((a, m): (u128, u128), (b, n): (u128, u128)) -> Option<(u128, u128)> {
+    if m == 0 || n == 0 {
+        return None;
+    }
+    let g = gcd(m, n)?;
+    let (a, b) = (a % m, b % n);
+    if a % g != b % g {
+        return None;
+    }
+    let lcm = (m / g).checked_mul(n)?;
+    let reduced = n / g;
+    // x = a + k * m, where m / g * k == (b - a) / g (mod n / g)
+    let diff = sub_mod(b, a % n, n) / g;
+    let k = mul_mod(diff, mod_inverse(m / g, reduced)?, reduced);
+    Some((a + k * m, lcm))
+}
+
+/// Folds all `(residue, modulus)` congruences with [`crt`]; `(0, 1)` for none.
+pub fn crt_all(congruences: impl IntoIterator<Item = (u128, u128)>) -> Option<(u128, u128)> {
+    congruences.into_iter().try_fold((0, 1), crt)
+}
+
+/// Largest `r` with `r * r <= n`, computed exactly in integers.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above converges monotonically onto the floor of the root
+    let mut x: u128 = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12u32, 18), Some(6));
+        assert_eq!(gcd(-12i32, 18), Some(6));
+        assert_eq!(gcd(12i32, -18), Some(6));
+        assert_eq!(gcd(0u8, 0), Some(0));
+        assert_eq!(gcd(0u8, 7), Some(7));
+        assert_eq!(lcm(4u8, 6), Some(12));
+        assert_eq!(lcm(-4i64, 6), Some(12));
+        assert_eq!(lcm(0u64, 6), Some(0));
+    }
+
+    #[test]
+    fn gcd_and_lcm_overflow_to_none() {
+        assert_eq!(gcd(i64::MIN, 0), None);
+        assert_eq!(gcd(i64::MIN, i64::MIN), None);
+        assert_eq!(gcd(i64::MIN, -1), Some(1));
+        assert_eq!(gcd(i64::MIN, 6), Some(2));
+        assert_eq!(lcm(200u8, 3), None);
+        assert_eq!(lcm(i32::MIN, 3), None);
+        assert_eq!(lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn gcd_and_lcm_of_many() {
+        assert_eq!(gcd_all([12u64, 18, 30]), Some(6));
+        assert_eq!(gcd_all(Vec::<u32>::new()), Some(0));
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all(Vec::<u32>::new()), Some(1));
+        assert_eq!(lcm_all(1..=100u64), None);
+    }
+
+    #[test]
+    fn extended_euclid() {
+        for (a, b) in [(240, 46), (46, 240), (-240, 46), (17, 0), (0, 17), (1 << 100, 3)] {
+            let (g, x, y) = ext_gcd(a, b);
+            assert_eq!(a * x + b * y, g);
+            assert_eq!(g.unsigned_abs(), gcd(a, b).unwrap().unsigned_abs());
+        }
+    }
+
+    #[test]
+    fn modular_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(14, 11), Some(4));
+        assert_eq!(mod_inverse(2, 4), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(mod_inverse(5, 0), None);
+        for m in 1..60u128 {
+            for a in 0..m {
+                let brute = (0..m).find(|x| a * x % m == 1 % m);
+                assert_eq!(mod_inverse(a, m), brute, "{a} mod {m}");
+            }
+        }
+        let m = u128::MAX - 158;
+        let inverse = mod_inverse(7, m).unwrap();
+        assert_eq!(mul_mod(7, inverse, m), 1);
+    }
+
+    #[test]
+    fn multiplication_near_the_limit() {
+        let m = u128::MAX;
+        assert_eq!(mul_mod(m - 1, m - 1, m), 1);
+        assert_eq!(mul_mod(m - 1, 2, m), m - 2);
+        assert_eq!(mul_mod(1 << 127, 4, m - 1), 4);
+        let square = u64::MAX as u128 * u64::MAX as u128;
+        assert_eq!(mul_mod(u64::MAX as u128, u64::MAX as u128, 1 << 100), square % (1 << 100));
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt_all([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt_all([]), Some((0, 1)));
+        assert_eq!(crt((7, 3), (8, 5)), Some((13, 15)));
+    }
+
+    #[test]
+    fn chinese_remainders_with_common_factors() {
+        assert_eq!(crt((2, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+        assert_eq!(crt((3, 6), (3, 6)), Some((3, 6)));
+        assert_eq!(crt((1, 0), (1, 2)), None);
+        for m in 1..25u128 {
+            for n in 1..25u128 {
+                for a in 0..m {
+                    for b in 0..n {
+                        let brute = (0..m * n).find(|x| x % m == a && x % n == b);
+                        assert_eq!(crt((a, m), (b, n)).map(|(c, _)| c), brute, "{a} mod {m}, {b} mod {n}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn chinese_remainders_overflowing_the_lcm() {
+        assert_eq!(crt((0, 1 << 127), (1, 3)).map(|(_, lcm)| lcm), None);
+        assert_eq!(crt((5, 1 << 126), (1, 2)), Some((5, 1 << 126)));
+    }
+
+    #[test]
+    fn integer_square_roots() {
+        for n in 0..10_000u128 {
+            let root = isqrt(n);
+            assert!(root * root <= n && (root + 1) * (root + 1) > n, "{n}");
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        for root in [u64::MAX as u128 - 1, 1 << 63, 3_037_000_499, 1_000_000_007] {
+            assert_eq!(isqrt(root * root), root);
+            assert_eq!(isqrt(root * root - 1), root - 1);
+            assert_eq!(isqrt(root * root + 1), root);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Small parsers for the line formats that keep coming back.
+//!
+//! All of them work on a single line (or section) and report failures as a [`ParseError`]
+//! pointing at the offending column; callers iterating over lines attach the line number with
+//! [`ParseError::at_line`].
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A parse failure at a 1-based character `column`, optionally within a 1-based `line`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates an error pointing at `part`, which has to be a subslice of `within`.
+    pub fn at(within: &str, part: &str, message: impl Into<String>) -> Self {
+        ParseError { line: None, column: column_of(within, part), message: message.into() }
+    }
+
+    /// Like [`ParseError::at`], but for a `part` of the multi-line `text`, so the line is known.
+    pub fn within(text: &str, part: &str, message: impl Into<String>) -> Self {
+        let offset = offset_of(text, part);
+        let line_start = text[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+        ParseError {
+            line: Some(text[..offset].matches('\n').count() + 1),
+            column: column_of(&text[line_start..], part),
+            message: message.into(),
+        }
+    }
+
+    /// Re-bases an error about the subslice `part` onto the enclosing `within`.
+    pub fn inside(mut self, within: &str, part: &str) -> Self {
+        self.column += column_of(within, part) - 1;
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Attaches the line of the multi-line `text` that `line` is a subslice of.
+    pub fn in_text(self, text: &str, line: &str) -> Self {
+        let line_no = text[..offset_of(text, line)].matches('\n').count() + 1;
+        self.at_line(line_no)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{line}:{}: {}", self.column, self.message),
+            None => write!(f, "column {}: {}", self.column, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Byte offset at which the subslice `part` starts within `within`, clamped to its bounds.
+fn offset_of(within: &str, part: &str) -> usize {
+    (part.as_ptr() as usize).saturating_sub(within.as_ptr() as usize).min(within.len())
+}
+
+/// 1-based character column at which the subslice `part` starts within `within`.
+fn column_of(within: &str, part: &str) -> usize {
+    within[..offset_of(within, part)].chars().count() + 1
+}
+
+/// Splits a `<label> <id>: <rest>` record such as `Card  12: 41 48 | 83 86` into its id and
+/// the rest, trimmed. Any amount of whitespace may surround the id.
+pub fn id_record<'a, T: FromStr>(line: &'a str, label: &str) -> Result<(T, &'a str), ParseError> {
+    let tail = line.trim_start().strip_prefix(label)
+        .ok_or_else(|| ParseError::at(line, line.trim_start(), format!("expected {label:?}")))?;
+    let (raw_id, rest) = tail.split_once(':')
+        .ok_or_else(|| ParseError::at(line, tail, "expected ':' after the id"))?;
+    let id = raw_id.trim().parse()
+        .map_err(|_| ParseError::at(line, raw_id.trim(), format!("invalid id {:?}", raw_id.trim())))?;
+    Ok((id, rest.trim()))
+}
+
+/// Parses a whitespace-separated list of numbers; use a signed `T` to allow negative values.
+pub fn numbers<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    s.split_whitespace()
+        .map(|word| word.parse().map_err(|_| ParseError::at(s, word, format!("invalid number {word:?}"))))
+        .collect()
+}
+
+/// Parses a whitespace-separated list of exactly `N` numbers.
+pub fn exact_numbers<T: FromStr, const N: usize>(s: &str) -> Result<[T; N], ParseError> {
+    let values: Vec<T> = numbers(s)?;
+    let count = values.len();
+    values.try_into().map_err(|_| ParseError::at(s, s, format!("expected {N} numbers, found {count}")))
+}
+
+/// Splits a `key = (a, b)` line into its three parts, trimmed.
+pub fn key_tuple(line: &str) -> Result<(&str, &str, &str), ParseError> {
+    let (key, tuple) = line.split_once('=')
+        .ok_or_else(|| ParseError::at(line, line, "expected '='"))?;
+    let inner = tuple.trim()
+        .strip_prefix('(')
+        .and_then(|tuple| tuple.strip_suffix(')'))
+        .ok_or_else(|| ParseError::at(line, tuple.trim(), "expected \"(a, b)\""))?;
+    let (a, b) = inner.split_once(',')
+        .ok_or_else(|| ParseError::at(line, inner, "expected ',' between the tuple values"))?;
+    Ok((key.trim(), a.trim(), b.trim()))
+}
+
+/// Splits text into blank-line separated sections, each made of a `<header>:` line and the
+/// lines below it, and returns the headers (without the colon) with their bodies.
+///
+/// A header may carry content after its colon, as in `seeds: 79 14 55 13`, which then starts
+/// the body.
+pub fn labelled_sections(text: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    crate::input::sections(text)
+        .into_iter()
+        .map(|section| {
+            let (header, body) = section.split_once(':')
+                .filter(|(header, _)| !header.contains('\n'))
+                .ok_or_else(|| ParseError::within(text, section, "expected a \"<header>:\" line"))?;
+            Ok((header.trim(), body.trim()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(err: ParseError) -> (Option<usize>, usize, String) {
+        (err.line, err.column, err.message)
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let line = "ab cd";
+        assert_eq!(position(ParseError::at(line, &line[3..], "here")), (None, 4, "here".to_string()));
+        let line = "héllo wörld";
+        assert_eq!(ParseError::at(line, &line[line.find('w').unwrap()..], "here").column, 7);
+        assert_eq!(ParseError::at(line, &line[line.len()..], "here").column, 12);
+    }
+
+    #[test]
+    fn within_finds_line_and_column() {
+        let text = "first\nsecond line\nthïrd x";
+        let err = ParseError::within(text, &text[text.find("line").unwrap()..][..4], "here");
+        assert_eq!((err.line, err.column), (Some(2), 8));
+        let err = ParseError::within(text, &text[text.len() - 1..], "here");
+        assert_eq!((err.line, err.column), (Some(3), 7));
+        let err = ParseError::within(text, text, "here");
+        assert_eq!((err.line, err.column), (Some(1), 1));
+        assert_eq!(err.to_string(), "1:1: here");
+        assert_eq!(ParseError::at("x", "x", "here").to_string(), "column 1: here");
+    }
+
+    #[test]
+    fn numbers_nested_in_a_record_report_their_place_in_the_text() {
+        let text = "Card 1: 1 | 2\nCard 2: 3 | 4\nCard  12: 41 x8 | 83 86";
+        let line = text.lines().nth(2).unwrap();
+        let (id, rest): (u32, &str) = id_record(line, "Card").unwrap();
+        assert_eq!((id, rest), (12, "41 x8 | 83 86"));
+        let (winning, _) = rest.split_once('|').unwrap();
+        let err = numbers::<u32>(winning).unwrap_err();
+        assert_eq!(err.column, 4);
+        let err = err.inside(line, winning).in_text(text, line);
+        assert_eq!(position(err), (Some(3), 14, "invalid number \"x8\"".to_string()));
+    }
+
+    #[test]
+    fn id_records() {
+        assert_eq!(id_record::<u32>("  Game 3:  x y ", "Game"), Ok((3, "x y")));
+        assert_eq!(position(id_record::<u32>("Card 3: x", "Game").unwrap_err()),
+                   (None, 1, "expected \"Game\"".to_string()));
+        assert_eq!(position(id_record::<u32>("Game 3 x", "Game").unwrap_err()),
+                   (None, 5, "expected ':' after the id".to_string()));
+        assert_eq!(position(id_record::<u32>("Game  -3: x", "Game").unwrap_err()),
+                   (None, 7, "invalid id \"-3\"".to_string()));
+    }
+
+    #[test]
+    fn exact_number_counts() {
+        assert_eq!(exact_numbers::<i64, 3>(" 1  -2 3"), Ok([1, -2, 3]));
+        assert_eq!(position(exact_numbers::<i64, 3>("1 2").unwrap_err()),
+                   (None, 1, "expected 3 numbers, found 2".to_string()));
+        assert_eq!(exact_numbers::<u8, 2>("1 300").unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn key_tuples() {
+        assert_eq!(key_tuple("AAA = (BBB, CCC)"), Ok(("AAA", "BBB", "CCC")));
+        assert_eq!(key_tuple(" 11A=( 11B ,XXX )"), Ok(("11A", "11B", "XXX")));
+        assert_eq!(position(key_tuple("AAA (BBB, CCC)").unwrap_err()), (None, 1, "expected '='".to_string()));
+        assert_eq!(key_tuple("AAA = BBB, CCC").unwrap_err().column, 7);
+        assert_eq!(position(key_tuple("AAA = (BBB CCC)").unwrap_err()),
+                   (None, 8, "expected ',' between the tuple values".to_string()));
+    }
+
+    #[test]
+    fn labelled_sections_with_and_without_inline_content() {
+        let text = "seeds: 1 2\n\nsoil map:\n1 2 3\n4 5 6\n";
+        assert_eq!(labelled_sections(text), Ok(vec![("seeds", "1 2"), ("soil map", "1 2 3\n4 5 6")]));
+        let err = labelled_sections("seeds: 1\n\nno header\n1 2 3:\n").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(3), 1));
+    }
+}
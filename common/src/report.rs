@@ -0,0 +1,149 @@
+//! Printing the answers of a run, either as `first = …` lines or as JSON records.
+//!
+//! With `--format json` every answer is printed to stdout as a single-line JSON object such as
+//! `{"day": 8, "part": "first", "answer": 11911, "duration": 0.000412}`, with the duration in
+//! seconds. Failures become `{"day": 8, "part": "second", "error": "…"}`, without a `part` if
+//! the whole run failed. Diagnostics go to stderr in either format, as do listings printed with
+//! [`Report::detail`] in JSON, so stdout stays parseable.
+
+use std::fmt::{self, Display, Write};
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format {s:?}, expected \"text\" or \"json\"")),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Text => write!(f, "text"),
+            Format::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Prints the answers and failures of one day in the chosen [`Format`].
+#[derive(Debug, Copy, Clone)]
+pub struct Report {
+    day: u32,
+    format: Format,
+}
+
+impl Report {
+    pub fn new(day: u32, format: Format) -> Self {
+        Report { day, format }
+    }
+
+    /// Takes `--format <text|json>` out of `args`, so the remaining flags can be handled as
+    /// before; defaults to text.
+    pub fn from_args(day: u32, args: &mut Vec<String>) -> Self {
+        let mut report = Report::new(day, Format::Text);
+        if let Some(index) = args.iter().position(|arg| arg == "--format") {
+            let value = args.get(index + 1).cloned();
+            args.drain(index..args.len().min(index + 2));
+            report.format = match value.map(|value| value.parse()) {
+                Some(Ok(format)) => format,
+                Some(Err(err)) => report.fail(err),
+                None => report.fail("missing format after --format"),
+            };
+        }
+        report
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Runs `solve` and prints its answer to `part` together with the time it took.
+    pub fn answer<T: Display>(&self, part: &str, solve: impl FnOnce() -> T) {
+        let start = Instant::now();
+        let answer = solve();
+        self.record(part, answer, start.elapsed());
+    }
+
+    /// Like [`Report::answer`], but prints an error record instead if `solve` fails.
+    pub fn outcome<T: Display, E: Display>(&self, part: &str, solve: impl FnOnce() -> Result<T, E>) {
+        let start = Instant::now();
+        match solve() {
+            Ok(answer) => self.record(part, answer, start.elapsed()),
+            Err(err) => self.error(Some(part), err),
+        }
+    }
+
+    /// Prints an answer that was computed elsewhere.
+    pub fn record(&self, part: &str, answer: impl Display, duration: Duration) {
+        match self.format {
+            Format::Text => println!("{part} = {answer}"),
+            Format::Json => println!("{{\"day\": {}, \"part\": {}, \"answer\": {}, \"duration\": {}}}",
+                                     self.day, json_string(part), json_value(&answer.to_string()),
+                                     duration.as_secs_f64()),
+        }
+    }
+
+    /// Prints a failure, of a single part or of the whole run if `part` is `None`.
+    pub fn error(&self, part: Option<&str>, err: impl Display) {
+        match (self.format, part) {
+            (Format::Text, Some(part)) => eprintln!("{part}: {err}"),
+            (Format::Text, None) => eprintln!("error: {err}"),
+            (Format::Json, Some(part)) => println!("{{\"day\": {}, \"part\": {}, \"error\": {}}}",
+                                                   self.day, json_string(part), json_string(&err.to_string())),
+            (Format::Json, None) => println!("{{\"day\": {}, \"error\": {}}}",
+                                             self.day, json_string(&err.to_string())),
+        }
+    }
+
+    /// Prints a listing that is not an answer, such as an analysis or a rendering: to stdout in
+    /// text format, to stderr in JSON so that stdout only holds records.
+    pub fn detail(&self, text: impl Display) {
+        match self.format {
+            Format::Text => print!("{text}"),
+            Format::Json => eprint!("{text}"),
+        }
+    }
+
+    /// Prints a failure of the whole run and exits with status 1.
+    pub fn fail(&self, err: impl Display) -> ! {
+        self.error(None, err);
+        process::exit(1)
+    }
+}
+
+/// `s` as a JSON string literal.
+pub fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Integers stay JSON numbers, anything else becomes a string.
+fn json_value(answer: &str) -> String {
+    match answer.parse::<i128>() {
+        Ok(_) if !answer.starts_with('+') => answer.to_string(),
+        _ => json_string(answer),
+    }
+}
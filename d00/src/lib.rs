@@ -0,0 +1,34 @@
+//! Template for a new day: parse the input into a model type and solve both parts on it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use common::parse::ParseError;
+
+/// One parsed line of the input.
+pub struct Str {
+}
+
+impl fmt::Display for Str {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "test")
+    }
+}
+
+impl FromStr for Str {
+    type Err = ParseError;
+
+    fn from_str(_s: &str) -> Result<Self, Self::Err> {
+        Ok(Str {  })
+    }
+}
+
+/// Solves the first part.
+pub fn first(_strct: &[Str]) -> usize {
+    0
+}
+
+/// Solves the second part.
+pub fn second(_strct: &[Str]) -> usize {
+    0
+}
@@ -1,44 +1,24 @@
 use std::env;
-use std::fs;
-use std::fmt;
-use std::str::FromStr;
-use std::num::ParseIntError;
+
+use common::input;
+use common::log;
+use common::report::Report;
+use d00::{first, second, Str};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let input: String = fs::read_to_string(&args[1])
-        .expect("Input file missing");
+    let mut args: Vec<String> = env::args().collect();
+    log::from_args(&mut args);
+    let report = Report::from_args(0, &mut args);
+    let input: String = input::read(args.get(1).unwrap_or_else(|| report.fail("missing input path")))
+        .unwrap_or_else(|err| report.fail(format!("cannot read input: {err}")));
 
     let strct: Vec<Str> = input
         .lines()
-        .filter_map(|s| s.parse::<Str>().ok())
+        .enumerate()
+        .map(|(no, line)| line.parse::<Str>()
+            .unwrap_or_else(|err| report.fail(format!("invalid input: {}", err.at_line(no + 1)))))
         .collect();
 
-    println!("first = {}", first());
-    println!("second = {}", second());
-}
-
-struct Str {
+    report.answer("first", || first(&strct));
+    report.answer("second", || second(&strct));
 }
-
-impl fmt::Display for Str {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "test")
-    }
-}
-
-impl FromStr for Str {
-    type Err = ParseIntError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Str {  })
-    }
-}
-
-fn first() -> usize {
-    0
-}
-
-fn second() -> usize {
-    0
-}
\ No newline at end of file
@@ -0,0 +1,80 @@
+//! Day 1: recovering calibration values from the first and last number on each line.
+
+use std::fmt::Write;
+use std::io;
+use std::io::BufRead;
+
+use common::input;
+
+pub mod matcher;
+pub mod words;
+
+use matcher::Matcher;
+
+/// Sums the calibration values of all lines using digits only.
+pub fn first(input: &str, digits: &Matcher) -> u32 {
+    input
+        .lines()
+        .map(|line| calibration_value(line, digits))
+        .sum()
+}
+
+/// Sums the calibration values of all lines using digits and number words.
+pub fn second(input: &str, words: &Matcher) -> u32 {
+    input
+        .lines()
+        .map(|line| calibration_value(line, words))
+        .sum()
+}
+
+/// Computes both parts in a single pass over `reader`, holding one line at a time.
+pub fn calibrate_stream(reader: impl BufRead, digits: &Matcher, words: &Matcher) -> io::Result<(u32, u32)> {
+    let mut sums = (0, 0);
+    for line in input::lines(reader) {
+        let line = line?;
+        sums.0 += calibration_value(&line, digits);
+        sums.1 += calibration_value(&line, words);
+    }
+    Ok(sums)
+}
+
+/// Combines the leading digit of the first number and the trailing digit of the last number in
+/// `line`, or 0 if there is none.
+pub fn calibration_value(line: &str, matcher: &Matcher) -> u32 {
+    match (matcher.first(line), matcher.last(line)) {
+        (Some(first), Some(last)) => leading_digit(first.value) * 10 + last.value % 10,
+        _ => 0,
+    }
+}
+
+/// Lists the first and last number picked from every line with their character and byte
+/// offsets and the resulting value; every line that contributed nothing is also logged as a
+/// warning naming `part`.
+pub fn explain(input: &str, matcher: &Matcher, part: &str) -> String {
+    let mut out = String::new();
+    let mut empty: Vec<usize> = Vec::new();
+    for (no, line) in input.lines().enumerate() {
+        match (matcher.first(line), matcher.last(line)) {
+            (Some(first), Some(last)) => writeln!(out, "{:>5}: {:?} at char {} (byte {}) .. {:?} at char {} (byte {}) -> {}",
+                no + 1,
+                first.text(line), first.char_pos, first.byte_pos,
+                last.text(line), last.char_pos, last.byte_pos,
+                calibration_value(line, matcher)).unwrap(),
+            _ => {
+                writeln!(out, "{:>5}: no number -> 0", no + 1).unwrap();
+                empty.push(no + 1);
+            },
+        }
+    }
+    for no in empty {
+        common::warn!("{part}: line {no} contributed nothing");
+    }
+    out
+}
+
+fn leading_digit(mut number: u32) -> u32 {
+    while number >= 10 {
+        number /= 10;
+    }
+    number
+}
@@ -1,74 +1,64 @@
 use std::env;
 use std::fs;
+use std::io;
+use std::time::Instant;
+
+use common::input;
+use common::log;
+use common::report::Report;
+use d01::{calibrate_stream, first, second};
+use d01::matcher::Matcher;
+use d01::words::Dictionary;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let input: String = fs::read_to_string(&args[1])
-        .expect("Input file missing");
+    let mut args: Vec<String> = env::args().collect();
+    log::from_args(&mut args);
+    let report = Report::from_args(1, &mut args);
 
-    println!("first = {}", first(&input));
-    println!("second = {}", second(&input));
-}
+    // `--words en,de,my-words.txt` swaps the number words used by the second part,
+    // `--stream` reads the input line by line instead of loading it at once,
+    // `--explain` shows which numbers were picked from every line
+    let mut dictionary = Dictionary::digits();
+    let mut stream = false;
+    let mut explain = false;
+    let mut flags = args.iter().skip(2);
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--words" => {
+                let spec = flags.next().unwrap_or_else(|| report.fail("missing dictionary after --words"));
+                dictionary = Dictionary::load(spec)
+                    .unwrap_or_else(|err| report.fail(format!("invalid dictionary {spec}: {err}")));
+            },
+            "--stream" => stream = true,
+            "--explain" => explain = true,
+            other => report.fail(format!("unknown argument: {other}")),
+        }
+    }
+    let path = args.get(1).unwrap_or_else(|| report.fail("missing input path"));
+    let digits = Matcher::new(&Dictionary::empty());
+    let words = Matcher::new(&dictionary);
 
-fn first(input: &String) -> u32 {
-    input
-        .split("\n")
-        .map(|line| {
-            line
-                .chars()
-                .filter_map(|c| c.to_digit(10))
-                .collect::<Vec<u32>>()
-            })
-        .map(|digits|
-            match digits.last() {
-                Some(last) => digits[0] * 10 + last,
-                None => 0,
-            })
-        .sum()
-}
+    if stream {
+        let file = fs::File::open(path)
+            .unwrap_or_else(|err| report.fail(format!("cannot read input: {err}")));
+        // both parts come out of the same pass, so they share its duration
+        let start = Instant::now();
+        let (first, second) = calibrate_stream(io::BufReader::new(file), &digits, &words)
+            .unwrap_or_else(|err| report.fail(format!("cannot read input: {err}")));
+        let duration = start.elapsed();
+        report.record("first", first, duration);
+        report.record("second", second, duration);
+        return;
+    }
 
-fn second(input: &String) -> u32 {
-    input
-        .lines()
-        .map(digits_from_line)
-        .map(|digits|
-            match digits.last() {
-                Some(last) => digits[0] * 10 + last,
-                None => 0,
-            })
-        .sum()
-}
+    let input: String = input::read(path)
+        .unwrap_or_else(|err| report.fail(format!("cannot read input: {err}")));
 
-fn digits_from_line(line: &str) -> Vec<u32> {
-    let mut digits: Vec<u32> = Vec::new();
-    for (pos, c) in line.chars().enumerate() {
-        if let Some(digit) = c.to_digit(10) {
-            digits.push(digit);
-        } else if let Some(digit) = digit_from_word(&line[pos..]) {
-            digits.push(digit);
-        }
+    if explain {
+        report.detail(format!("first:\n{}", d01::explain(&input, &digits, "first")));
+        report.detail(format!("second:\n{}", d01::explain(&input, &words, "second")));
     }
-    return digits;
-}
 
-fn digit_from_word(slice: &str) -> Option<u32> {
-    for (digit, word) in DIGITS.iter().enumerate() {
-        if slice.starts_with(word) {
-            return Some(digit as u32);
-        }
-    }
-    return None;
+    report.answer("first", || first(&input, &digits));
+    report.answer("second", || second(&input, &words));
 }
-
-const DIGITS: [&str; 10] = [
-    "zero",
-    "one",
-    "two",
-    "three",
-    "four",
-    "five",
-    "six",
-    "seven",
-    "eight",
-    "nine",
-];
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use crate::words::Dictionary;
+
+/// Trie over the number words of a [`Dictionary`] plus the ten digit characters.
+///
+/// Lines are scanned from the front for the first match and from the back for the last one,
+/// so neither end needs to look at the middle of the line once a match is found. At every
+/// position the longest word wins, and overlapping words such as "oneight" are found from
+/// both sides.
+///
+/// The first number is the one starting furthest left and the last number the one ending
+/// furthest right, the longer one winning ties. So a word contained in a longer word at its
+/// start or end never beats it: "achtzehn" is 18 from both sides, not 8 or 10.
+pub struct Matcher {
+    nodes: Vec<Node>,
+    /// Length in bytes of the longest word.
+    max_len: usize,
+}
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<char, usize>,
+    value: Option<u32>,
+}
+
+/// A number found in a line, starting at byte offset `byte_pos` (the `char_pos`-th character)
+/// and spanning `len` bytes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Match {
+    pub byte_pos: usize,
+    pub char_pos: usize,
+    pub len: usize,
+    pub value: u32,
+}
+
+impl Match {
+    /// The matched part of `line`.
+    pub fn text<'a>(&self, line: &'a str) -> &'a str {
+        &line[self.byte_pos..self.byte_pos + self.len]
+    }
+}
+
+impl Matcher {
+    pub fn new(dictionary: &Dictionary) -> Self {
+        let mut matcher = Matcher { nodes: vec![Node::default()], max_len: 0 };
+        for digit in 0..10 {
+            matcher.insert(&digit.to_string(), digit);
+        }
+        for (word, value) in dictionary.words() {
+            matcher.insert(word, value);
+        }
+        matcher
+    }
+
+    fn insert(&mut self, word: &str, value: u32) {
+        let mut node = 0;
+        for c in word.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(c, child);
+                    child
+                },
+            };
+        }
+        self.nodes[node].value = Some(value);
+        self.max_len = self.max_len.max(word.len());
+    }
+
+    /// Returns the longest word starting at byte offset `byte_pos`, which has to lie on a
+    /// character boundary.
+    fn match_at(&self, line: &str, byte_pos: usize, char_pos: usize) -> Option<Match> {
+        let mut node = 0;
+        let mut longest = None;
+        for (offset, c) in line[byte_pos..].char_indices() {
+            match self.nodes[node].children.get(&c) {
+                Some(&child) => node = child,
+                None => break,
+            }
+            if let Some(value) = self.nodes[node].value {
+                longest = Some(Match { byte_pos, char_pos, len: offset + c.len_utf8(), value });
+            }
+        }
+        longest
+    }
+
+    pub fn first(&self, line: &str) -> Option<Match> {
+        line.char_indices()
+            .enumerate()
+            .find_map(|(char_pos, (byte_pos, _))| self.match_at(line, byte_pos, char_pos))
+    }
+
+    pub fn last(&self, line: &str) -> Option<Match> {
+        let chars = line.chars().count();
+        let mut last: Option<Match> = None;
+        for (offset, (byte_pos, _)) in line.char_indices().rev().enumerate() {
+            // nothing starting this far left can reach the end of the match found so far
+            if last.is_some_and(|last| byte_pos + self.max_len < last.byte_pos + last.len) {
+                break;
+            }
+            if let Some(found) = self.match_at(line, byte_pos, chars - 1 - offset) {
+                let end = found.byte_pos + found.len;
+                if last.is_none_or(|last| end >= last.byte_pos + last.len) {
+                    last = Some(found);
+                }
+            }
+        }
+        last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibration_value;
+
+    fn german() -> Matcher {
+        Matcher::new(&Dictionary::builtin("de").unwrap())
+    }
+
+    #[test]
+    fn teen_words_beat_the_words_they_end_with() {
+        let matcher = german();
+        for (word, value) in [("dreizehn", 13), ("vierzehn", 14), ("fünfzehn", 15), ("sechzehn", 16),
+                              ("siebzehn", 17), ("achtzehn", 18), ("neunzehn", 19)] {
+            assert_eq!(matcher.first(word).map(|m| m.value), Some(value), "{word}");
+            assert_eq!(matcher.last(word).map(|m| m.value), Some(value), "{word}");
+            assert_eq!(calibration_value(word, &matcher), 10 + value % 10, "{word}");
+        }
+        assert_eq!(calibration_value("siebzehn", &matcher), 17);
+    }
+
+    #[test]
+    fn last_number_is_the_one_ending_furthest_right() {
+        let matcher = german();
+        assert_eq!(matcher.last("achtzehn").map(|m| (m.byte_pos, m.value)), Some((0, 18)));
+        assert_eq!(matcher.last("xachtzehn1").map(|m| m.value), Some(1));
+        assert_eq!(matcher.last("zehnacht").map(|m| m.value), Some(8));
+        let english = Matcher::new(&Dictionary::digits());
+        assert_eq!(english.last("oneight").map(|m| m.value), Some(8));
+        assert_eq!(english.last("twone").map(|m| m.value), Some(1));
+    }
+
+    #[test]
+    fn accented_lines_report_char_and_byte_positions() {
+        let matcher = german();
+        let line = "é3fünfé";
+        let first = matcher.first(line).unwrap();
+        assert_eq!((first.char_pos, first.byte_pos, first.len, first.value), (1, 2, 1, 3));
+        assert_eq!(first.text(line), "3");
+        // "é" before the word, "ü" inside it and "é" after it
+        let last = matcher.last(line).unwrap();
+        assert_eq!((last.char_pos, last.byte_pos, last.len, last.value), (2, 3, 5, 5));
+        assert_eq!(last.text(line), "fünf");
+    }
+
+    #[test]
+    fn emoji_lines_report_char_and_byte_positions() {
+        let matcher = Matcher::new(&Dictionary::digits());
+        let line = "🎄two🎁1🎅";
+        let first = matcher.first(line).unwrap();
+        assert_eq!((first.char_pos, first.byte_pos, first.value), (1, 4, 2));
+        assert_eq!(first.text(line), "two");
+        let last = matcher.last(line).unwrap();
+        assert_eq!((last.char_pos, last.byte_pos, last.value), (5, 11, 1));
+        assert_eq!(last.text(line), "1");
+        assert_eq!(matcher.first("🎄🎁"), None);
+        assert_eq!(matcher.last("🎄🎁"), None);
+    }
+
+    #[test]
+    fn words_made_of_multi_byte_characters() {
+        let matcher = Matcher::new(&Dictionary::builtin("fr").unwrap());
+        let line = "ñzéroñ";
+        let found = matcher.first(line).unwrap();
+        assert_eq!((found.char_pos, found.byte_pos, found.len, found.value), (1, 2, 5, 0));
+        assert_eq!(matcher.last(line), Some(found));
+        assert_eq!(found.text(line), "zéro");
+    }
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::io;
+
+/// Number words recognised by the calibration decoder, each mapped to its value.
+///
+/// Values may have several digits ("twelve" = 12); a line's calibration value uses the leading
+/// digit of its first number and the trailing digit of its last one.
+pub struct Dictionary {
+    words: Vec<(String, u32)>,
+}
+
+const ENGLISH: [&str; 21] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen",
+    "eighteen", "nineteen", "twenty",
+];
+
+const GERMAN: [&str; 21] = [
+    "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+    "zehn", "elf", "zwölf", "dreizehn", "vierzehn", "fünfzehn", "sechzehn", "siebzehn",
+    "achtzehn", "neunzehn", "zwanzig",
+];
+
+const FRENCH: [&str; 21] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+    "dix", "onze", "douze", "treize", "quatorze", "quinze", "seize", "dix-sept",
+    "dix-huit", "dix-neuf", "vingt",
+];
+
+impl Dictionary {
+    /// The single-digit English words the puzzle is about.
+    pub fn digits() -> Self {
+        Dictionary::from_list(&ENGLISH[..10])
+    }
+
+    /// Returns the built-in dictionary for `lang` (`en`, `de` or `fr`).
+    pub fn builtin(lang: &str) -> Option<Self> {
+        match lang {
+            "en" => Some(Dictionary::from_list(&ENGLISH)),
+            "de" => Some(Dictionary::from_list(&GERMAN)),
+            "fr" => Some(Dictionary::from_list(&FRENCH)),
+            _ => None,
+        }
+    }
+
+    /// Reads a dictionary with one `word value` pair per line; blank lines and `#` comments are
+    /// skipped.
+    pub fn from_file(path: &str) -> io::Result<Self> {
+        let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("invalid word entry: {line:?}"));
+        let words = fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.rsplit_once(char::is_whitespace)
+                .and_then(|(word, value)| Some((word.trim().to_lowercase(), value.parse().ok()?)))
+                .ok_or_else(|| invalid(line)))
+            .collect::<io::Result<_>>()?;
+        Ok(Dictionary { words })
+    }
+
+    /// Resolves a comma-separated list of built-in languages and dictionary files.
+    pub fn load(spec: &str) -> io::Result<Self> {
+        let mut words = Vec::new();
+        for part in spec.split(',') {
+            let dictionary = match Dictionary::builtin(part) {
+                Some(dictionary) => dictionary,
+                None => Dictionary::from_file(part)?,
+            };
+            words.extend(dictionary.words);
+        }
+        Ok(Dictionary { words })
+    }
+
+    fn from_list(words: &[&str]) -> Self {
+        Dictionary {
+            words: words.iter()
+                .enumerate()
+                .map(|(value, word)| (word.to_string(), value as u32))
+                .collect(),
+        }
+    }
+
+    /// No words at all, leaving only the digits themselves.
+    pub fn empty() -> Self {
+        Dictionary { words: Vec::new() }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words.iter().map(|(word, value)| (word.as_str(), *value))
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeSet;
+
+use crate::{CubeSet, Game};
+
+/// The smallest bag each game is possible with, by game id.
+pub fn minimal_bags(games: &[Game]) -> Vec<(u32, CubeSet)> {
+    games.iter()
+        .map(|game| (game.id, game.minimal_bag()))
+        .collect()
+}
+
+/// Ids of the games possible with each of the given bags.
+pub fn possible_games(games: &[Game], bags: &[CubeSet]) -> Vec<Vec<u32>> {
+    bags.iter()
+        .map(|bag| games.iter()
+            .filter(|game| game.is_possible_with(bag))
+            .map(|game| game.id)
+            .collect())
+        .collect()
+}
+
+/// Cubes that would have to be added to `bag` to make `game` possible.
+pub fn shortfall(game: &Game, bag: &CubeSet) -> CubeSet {
+    let cubes = game.minimal_bag().cubes.into_iter()
+        .filter(|(colour, count)| *count > bag.count(colour))
+        .map(|(colour, count)| {
+            let missing = count - bag.count(&colour);
+            (colour, missing)
+        })
+        .collect();
+    CubeSet { cubes }
+}
+
+/// Bags that make the most games possible for their total number of cubes.
+///
+/// Every bag on the frontier makes strictly more games possible than any smaller bag, and no bag
+/// of the same or smaller size does better. Candidates combine, per colour, the counts occurring in
+/// the games' minimal bags, so the search grows with the product of those counts over all colours.
+pub fn frontier(games: &[Game]) -> Vec<(CubeSet, Vec<u32>)> {
+    let minimal: Vec<CubeSet> = games.iter().map(Game::minimal_bag).collect();
+    let colours: BTreeSet<&str> = minimal.iter().flat_map(CubeSet::colours).collect();
+
+    let mut candidates: Vec<CubeSet> = vec![CubeSet::default()];
+    for colour in colours {
+        let counts: BTreeSet<u32> = minimal.iter().map(|bag| bag.count(colour)).collect();
+        candidates = candidates.iter()
+            .flat_map(|bag| counts.iter().map(move |&count| {
+                let mut bag = bag.clone();
+                bag.cubes.push((colour.to_string(), count));
+                bag
+            }))
+            .collect();
+    }
+    candidates.sort_by_key(CubeSet::total);
+
+    let mut frontier: Vec<(CubeSet, Vec<u32>)> = Vec::new();
+    for bag in candidates {
+        let ids: Vec<u32> = games.iter()
+            .zip(minimal.iter())
+            .filter(|(_, needed)| needed.fits_in(&bag))
+            .map(|(game, _)| game.id)
+            .collect();
+        if ids.is_empty() {
+            continue;
+        }
+        match frontier.last_mut() {
+            Some((last, last_ids)) if last.total() == bag.total() && last_ids.len() < ids.len() =>
+                (*last, *last_ids) = (bag, ids),
+            Some((_, last_ids)) if last_ids.len() >= ids.len() => {},
+            _ => frontier.push((bag, ids)),
+        }
+    }
+    frontier
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn games(lines: &[&str]) -> Vec<Game> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    fn bag(s: &str) -> CubeSet {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn possible_games_per_bag() {
+        let games = games(&["Game 1: 3 blue, 4 red", "Game 2: 1 red, 2 green", "Game 3: 20 red"]);
+        let bags = [bag("4 red, 3 blue"), bag("2 green, 1 red"), bag("20 red, 2 green, 3 blue"), bag("1 blue")];
+        assert_eq!(possible_games(&games, &bags), [vec![1], vec![2], vec![1, 2, 3], vec![]]);
+    }
+
+    #[test]
+    fn shortfall_lists_only_missing_cubes() {
+        let game: Game = "Game 1: 3 blue, 4 red; 1 red, 2 green".parse().unwrap();
+        assert_eq!(shortfall(&game, &bag("1 blue, 9 red")), bag("2 blue, 2 green"));
+        assert_eq!(shortfall(&game, &bag("3 blue, 4 red, 2 green")), CubeSet::default());
+    }
+
+    #[test]
+    fn frontier_skips_bags_making_no_game_possible() {
+        let games = games(&["Game 1: 3 blue, 4 red", "Game 2: 1 red, 2 green", "Game 3: 20 red"]);
+        let frontier: Vec<(String, Vec<u32>)> = frontier(&games).into_iter()
+            .map(|(bag, ids)| (bag.to_string(), ids))
+            .collect();
+        assert_eq!(frontier, [
+            ("0 blue, 2 green, 1 red".to_string(), vec![2]),
+            ("3 blue, 2 green, 4 red".to_string(), vec![1, 2]),
+            ("3 blue, 2 green, 20 red".to_string(), vec![1, 2, 3]),
+        ]);
+    }
+}
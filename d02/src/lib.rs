@@ -0,0 +1,199 @@
+//! Day 2: checking which cube games are possible with a given bag.
+
+use std::fmt;
+use std::str::FromStr;
+use std::cmp;
+
+use common::parse::{self, ParseError};
+
+pub mod feasibility;
+
+/// Number of cubes per colour, for any set of colours, in the order they were listed.
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct CubeSet {
+    cubes: Vec<(String, u32)>,
+}
+
+impl CubeSet {
+    /// Number of cubes of `colour`, 0 if absent.
+    pub fn count(&self, colour: &str) -> u32 {
+        self.cubes.iter()
+            .find(|(c, _)| c == colour)
+            .map_or(0, |&(_, count)| count)
+    }
+
+    /// Colours in the order they were listed.
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.cubes.iter().map(|(colour, _)| colour.as_str())
+    }
+
+    /// Whether every colour of `self` is available at least as often in `bag`.
+    pub fn fits_in(&self, bag: &CubeSet) -> bool {
+        self.cubes.iter().all(|(colour, count)| *count <= bag.count(colour))
+    }
+
+    /// Colour-wise maximum of both sets.
+    pub fn max(mut self, other: &CubeSet) -> CubeSet {
+        for (colour, count) in other.cubes.iter() {
+            match self.cubes.iter_mut().find(|(c, _)| c == colour) {
+                Some((_, max)) => *max = cmp::max(*max, *count),
+                None => self.cubes.push((colour.clone(), *count)),
+            }
+        }
+        self
+    }
+
+    /// Total number of cubes.
+    pub fn total(&self) -> u32 {
+        self.cubes.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Product of the counts of the given colours.
+    pub fn power<'a>(&self, colours: impl Iterator<Item = &'a str>) -> u32 {
+        colours.map(|colour| self.count(colour)).product()
+    }
+}
+
+impl fmt::Display for CubeSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cubes: Vec<String> = self.cubes.iter()
+            .map(|(colour, count)| format!("{count} {colour}"))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+impl FromStr for CubeSet {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cubes: Vec<(String, u32)> = Vec::new();
+
+        for pair in s.split(", ") {
+            let (val, colour) = pair.split_once(' ')
+                .ok_or_else(|| GameError::InvalidDraw(s.to_string()))?;
+            let count = val.parse()
+                .map_err(|_| GameError::InvalidCount(val.to_string()))?;
+            if colour.is_empty() || colour.contains(' ') {
+                return Err(GameError::InvalidDraw(s.to_string()));
+            }
+            if cubes.iter().any(|(c, _)| c == colour) {
+                return Err(GameError::DuplicateColour(colour.to_string()));
+            }
+            cubes.push((colour.to_string(), count));
+        }
+        Ok(CubeSet { cubes })
+    }
+}
+
+/// One line of the input: the cube sets drawn in game `id`.
+#[derive(PartialEq, Debug)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<CubeSet>,
+}
+
+impl Game {
+    /// The smallest bag this game is possible with.
+    pub fn minimal_bag(&self) -> CubeSet {
+        self.draws.iter().fold(CubeSet::default(), CubeSet::max)
+    }
+
+    /// Whether every draw fits in `bag`.
+    pub fn is_possible_with(&self, bag: &CubeSet) -> bool {
+        self.draws.iter().all(|draw| draw.fits_in(bag))
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let draws: Vec<String> = self.draws.iter().map(CubeSet::to_string).collect();
+        write!(f, "Game {}: {}", self.id, draws.join("; "))
+    }
+}
+
+impl FromStr for Game {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, raw_draws) = parse::id_record(s, "Game")?;
+        let draws = raw_draws.split("; ")
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Game { id, draws })
+    }
+}
+
+/// Reasons a line or bag fails to parse.
+#[derive(Debug, PartialEq)]
+pub enum GameError {
+    /// The line is not of the form `Game <id>: <draws>`.
+    Syntax(ParseError),
+    InvalidDraw(String),
+    InvalidCount(String),
+    DuplicateColour(String),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Syntax(err) => write!(f, "{err}"),
+            GameError::InvalidDraw(draw) => write!(f, "invalid draw {draw:?}"),
+            GameError::InvalidCount(count) => write!(f, "invalid cube count {count:?}"),
+            GameError::DuplicateColour(colour) => write!(f, "colour {colour} drawn twice in one draw"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<ParseError> for GameError {
+    fn from(err: ParseError) -> Self {
+        GameError::Syntax(err)
+    }
+}
+
+/// Sums the ids of the games possible with `bag`.
+pub fn first(games: &[Game], bag: &CubeSet) -> u32 {
+    games.iter()
+        .filter(|game| game.is_possible_with(bag))
+        .map(|game| game.id)
+        .sum()
+}
+
+/// Sums the powers of the smallest bag per game, over the colours of `bag`.
+pub fn second(games: &[Game], bag: &CubeSet) -> u32 {
+    games.iter()
+        .map(|game| game.minimal_bag().power(bag.colours()))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_round_trips_through_display() {
+        let line = "Game 1: 3 blue, 4 red; 1 red";
+        let game: Game = line.parse().unwrap();
+        assert_eq!(game.id, 1);
+        assert_eq!((game.draws[0].count("red"), game.draws[1].count("blue")), (4, 0));
+        assert_eq!(game.to_string(), line);
+        assert_eq!(game.to_string().parse::<Game>(), Ok(game));
+    }
+
+    #[test]
+    fn bad_id_is_a_syntax_error() {
+        assert_eq!("Game x: 3 blue".parse::<Game>(),
+                   Err(GameError::Syntax(ParseError { line: None, column: 6, message: "invalid id \"x\"".to_string() })));
+        assert!(matches!("Gaem 1: 3 blue".parse::<Game>(), Err(GameError::Syntax(_))));
+    }
+
+    #[test]
+    fn bad_counts_and_duplicate_colours() {
+        assert_eq!("Game 1: 3 blue; -4 red".parse::<Game>(), Err(GameError::InvalidCount("-4".to_string())));
+        assert_eq!("many red".parse::<CubeSet>(), Err(GameError::InvalidCount("many".to_string())));
+        assert_eq!("Game 1: 3 blue, 4 red, 1 blue".parse::<Game>(), Err(GameError::DuplicateColour("blue".to_string())));
+        assert_eq!("3 blue; 3 blue".parse::<CubeSet>(), Err(GameError::InvalidDraw("3 blue; 3 blue".to_string())));
+    }
+}
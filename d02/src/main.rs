@@ -1,127 +1,77 @@
 use std::env;
-use std::fs;
-use std::fmt;
-use std::str::FromStr;
-use std::num::ParseIntError;
-use std::cmp;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let input: String = fs::read_to_string(&args[1])
-        .expect("Input file missing");
-
-    println!("first = {}", first(&input));
-    println!("second = {}", second(&input));
-}
-
-struct Triplet {
-    red: u32,
-    green: u32,
-    blue: u32,
-}
-
-impl fmt::Display for Triplet {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} red, {} green, {} blue; ", self.red, self.green, self.blue)
-    }
-}
-
-impl FromStr for Triplet {
-    type Err = ParseIntError;
+use common::input;
+use common::log;
+use common::report::Report;
+use d02::{first, second, CubeSet, Game};
+use d02::feasibility;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut red: u32 = 0;
-        let mut green: u32 = 0;
-        let mut blue: u32 = 0;
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    log::from_args(&mut args);
+    let report = Report::from_args(2, &mut args);
+    let input: String = input::read(args.get(1).unwrap_or_else(|| report.fail("missing input path")))
+        .unwrap_or_else(|err| report.fail(format!("cannot read input: {err}")));
 
-        for pair in s.split(", ") {
-            if let Some((val, cube)) = pair.split_once(" ") {
-                match cube {
-                    "red" => red = val.parse()?,
-                    "green" => green = val.parse()?,
-                    "blue" => blue = val.parse()?,
-                    &_ => {},
-                }
-            }
+    // `--bag "12 red, 13 green"` or `--bag-file <path>` replaces the bag of the first part,
+    // `--bags <path>` checks one bag per line, `--minimal` prints the smallest bag per game,
+    // `--frontier` prints the bags making the most games possible for their size
+    let mut bag: CubeSet = "12 red, 13 green, 14 blue".parse().unwrap();
+    let mut analyses: Vec<&str> = Vec::new();
+    let mut bags_file: Option<&String> = None;
+    let mut flags = args.iter().skip(2);
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--bag" => {
+                let spec = flags.next().unwrap_or_else(|| report.fail("missing bag after --bag"));
+                bag = spec.parse()
+                    .unwrap_or_else(|err| report.fail(format!("invalid bag {spec:?}: {err}")));
+            },
+            "--bag-file" => {
+                let path = flags.next().unwrap_or_else(|| report.fail("missing path after --bag-file"));
+                let spec = input::read(path)
+                    .unwrap_or_else(|err| report.fail(format!("cannot read bag file: {err}")));
+                bag = spec.trim().parse()
+                    .unwrap_or_else(|err| report.fail(format!("invalid bag in {path}: {err}")));
+            },
+            "--bags" => bags_file = Some(flags.next().unwrap_or_else(|| report.fail("missing path after --bags"))),
+            "--minimal" | "--frontier" => analyses.push(flag),
+            other => report.fail(format!("unknown argument: {other}")),
         }
-        Ok(Triplet { red, green, blue })
     }
-}
-
-fn first(input: &String) -> u32 {
-    let max = Triplet { red: 12, green: 13, blue: 14, };
 
-    input
+    let games: Vec<Game> = input
         .lines()
-        .map(line_to_game)
-        .map(|(index, game)|
-            (index, game
-                .split("; ")
-                .collect::<Vec<_>>()
-            ))
-        .map(|(index, grabs)|
-            (index, grabs
-                .iter()
-                .filter_map(|grab| grab.parse::<Triplet>().ok())
-                .collect::<Vec<_>>()
-            ))
-        .filter_map(|(index, triplets)|
-            match triplets
-                .iter()
-                .all(|triplet|
-                    triplet.red <= max.red &&
-                    triplet.green <= max.green &&
-                    triplet.blue <= max.blue
-                ) {
-                true => Some(index),
-                false => None,
-            }
-        )
-        .sum()
-}
-
-fn line_to_game(line: &str) -> (u32, &str) {
-    let game_index_start = line
-        .find(|c: char| c.is_digit(10))
-        .expect("no digit in line -> input invalid");
-    let game_index_length = &line[game_index_start..]
-        .find(|c: char| !c.is_digit(10))
-        .expect("no digit in line -> input invalid");
+        .enumerate()
+        .map(|(no, line)| line.parse::<Game>()
+            .unwrap_or_else(|err| report.fail(format!("invalid input in line {}: {err}", no + 1))))
+        .collect();
 
-    let mut factor: u32 = 1;
-    let mut game_index: u32 = 0;
-    for digit in &line[game_index_start..game_index_start + game_index_length].chars().rev() {
-        game_index += digit.to_digit(10).unwrap() * factor;
-        factor *= 10;
+    if analyses.contains(&"--minimal") {
+        for (id, bag) in feasibility::minimal_bags(&games) {
+            report.detail(format!("game {id}: {bag}\n"));
+        }
+        for game in games.iter().filter(|game| !game.is_possible_with(&bag)) {
+            report.detail(format!("game {} needs {} more\n", game.id, feasibility::shortfall(game, &bag)));
+        }
     }
-
-    return (game_index, &line[game_index_start + game_index_length + 2..]);
-}
-
-fn second(input: &String) -> u32 {
-    input
-        .lines()
-        .map(line_to_game)
-        .map(|(_, game)|
-            game
-                .split("; ")
-                .collect::<Vec<_>>())
-        .map(|grabs|
-            grabs
-                .iter()
-                .filter_map(|grab| grab.parse::<Triplet>().ok())
-                .collect::<Vec<_>>())
-        .map(|triplets|
-            triplets
-                .iter()
-                .fold(Triplet { red: 0, green: 0, blue: 0}, |a, b|
-                    Triplet {
-                        red: cmp::max(a.red, b.red),
-                        green: cmp::max(a.green, b.green),
-                        blue: cmp::max(a.blue, b.blue),
-                    }))
-        .map(|triplet|
-             triplet.red * triplet.green * triplet.blue
-        )
-        .sum()
+    if analyses.contains(&"--frontier") {
+        for (bag, ids) in feasibility::frontier(&games) {
+            report.detail(format!("{} cubes ({bag}): {} games\n", bag.total(), ids.len()));
+        }
+    }
+    if let Some(path) = bags_file {
+        let bags: Vec<CubeSet> = input::read(path)
+            .unwrap_or_else(|err| report.fail(format!("cannot read bags file: {err}")))
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.parse()
+                .unwrap_or_else(|err| report.fail(format!("invalid bag {line:?} in {path}: {err}"))))
+            .collect();
+        for (bag, ids) in bags.iter().zip(feasibility::possible_games(&games, &bags)) {
+            report.detail(format!("{bag}: {} games possible, id sum {}\n", ids.len(), ids.iter().sum::<u32>()));
+        }
+    }
+    report.answer("first", || first(&games, &bag));
+    report.answer("second", || second(&games, &bag));
 }
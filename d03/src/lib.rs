@@ -0,0 +1,317 @@
+//! Day 3: finding part numbers and gears in the engine schematic.
+
+use std::fmt;
+use std::fmt::Write;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use common::grid::{Grid, Pos};
+
+pub mod render;
+
+/// A horizontal run of digits in the schematic.
+pub struct Span {
+    pub id: usize,
+    pub number: String,
+    pub x: usize,
+    pub y: usize,
+    /// Symbol cells touching the span, in reading order.
+    pub symbols: Vec<Pos>,
+}
+
+impl Span {
+    /// The number the digits spell.
+    pub fn value(&self) -> usize {
+        self.number.parse().unwrap()
+    }
+
+    /// Positions of the digits.
+    pub fn cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        (self.x..self.x + self.number.len()).map(|x| Pos::new(x, self.y))
+    }
+
+    /// Returns the in-bounds cells of the ring around the span, in reading order.
+    ///
+    /// That is every cell of the rectangle one column left of the first digit to one column right
+    /// of the last digit and one row above to one row below, except the digits themselves.
+    pub fn border(&self, map: &Grid<char>) -> Vec<Pos> {
+        let mut border: Vec<Pos> = self.cells()
+            .flat_map(|cell| map.neighbours8(cell))
+            .filter(|pos| pos.y != self.y || !(self.x..self.x + self.number.len()).contains(&pos.x))
+            .collect();
+        border.sort_by_key(|pos| (pos.y, pos.x));
+        border.dedup();
+        border
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.number, Pos::new(self.x, self.y))
+    }
+}
+
+/// Engine schematic indexed by its number spans and the symbols next to them.
+pub struct Schematic {
+    map: Grid<char>,
+    spans: Vec<Span>,
+    /// Ids of the spans adjacent to each symbol cell, in ascending order.
+    adjacent: HashMap<Pos, Vec<usize>>,
+}
+
+/// Anything but digits and `.` is a symbol.
+pub fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
+}
+
+impl Schematic {
+    /// Indexes the spans of `map` and the symbols around them.
+    pub fn new(map: Grid<char>) -> Self {
+        let mut spans: Vec<Span> = Vec::new();
+        let mut adjacent: HashMap<Pos, Vec<usize>> = HashMap::new();
+
+        for (y, row) in map.rows().enumerate() {
+            let mut cur_span: Option<Span> = None;
+
+            // a trailing non-digit closes a span that reaches the end of line
+            for (x, &c) in row.iter().chain(['.'].iter()).enumerate() {
+                match (&mut cur_span, c.is_ascii_digit()) {
+                    (Some(span), true) => span.number.push(c),
+                    (None, true) => cur_span = Some(Span { id: spans.len(), number: c.to_string(), x, y, symbols: Vec::new() }),
+                    (Some(_), false) => spans.extend(cur_span.take()),
+                    (None, false) => {},
+                }
+            }
+        }
+
+        for span in spans.iter_mut() {
+            span.symbols = span.border(&map)
+                .into_iter()
+                .filter(|&pos| is_symbol(map[pos]))
+                .collect();
+            for &symbol in span.symbols.iter() {
+                adjacent.entry(symbol).or_default().push(span.id);
+            }
+        }
+
+        Schematic { map, spans, adjacent }
+    }
+
+    /// Iterates in reading order over every symbol accepted by `symbol` together with the spans
+    /// adjacent to it.
+    pub fn symbols<'a>(&'a self, symbol: impl Fn(char) -> bool + 'a) -> impl Iterator<Item = (Pos, char, Vec<&'a Span>)> + 'a {
+        self.map.iter()
+            .filter(move |(_, &c)| is_symbol(c) && symbol(c))
+            .map(|(pos, &c)| (pos, c, self.adjacent.get(&pos)
+                .map(|ids| ids.iter().map(|&id| &self.spans[id]).collect())
+                .unwrap_or_default()))
+    }
+
+    pub fn map(&self) -> &Grid<char> {
+        &self.map
+    }
+
+    /// All spans in reading order; a span's id is its index.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Returns all spans adjacent to at least one symbol, in reading order.
+    pub fn parts(&self) -> Vec<&Span> {
+        self.spans.iter()
+            .filter(|span| !span.symbols.is_empty())
+            .collect()
+    }
+
+    /// Lists every span with the symbols that made it count as a part, or why it was ignored.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        for span in self.spans.iter() {
+            let symbols: Vec<String> = span.symbols.iter()
+                .map(|&pos| format!("{} at {pos}", self.map[pos]))
+                .collect();
+            match symbols.is_empty() {
+                true => writeln!(report, "{span}: ignored, no adjacent symbol"),
+                false => writeln!(report, "{span}: counted, next to {}", symbols.join(", ")),
+            }.unwrap();
+        }
+        report
+    }
+}
+
+/// Number of spans a symbol must touch to be reported by a [`Query`].
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    /// Whether a symbol touching `count` spans is accepted.
+    pub fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exactly(n) => count == n,
+            Arity::AtLeast(n) => count >= n,
+        }
+    }
+}
+
+impl FromStr for Arity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |_| format!("invalid arity: {s:?}");
+        match s.strip_prefix(">=") {
+            Some(n) => n.parse().map(Arity::AtLeast).map_err(invalid),
+            None => s.trim_start_matches('=').parse().map(Arity::Exactly).map_err(invalid),
+        }
+    }
+}
+
+/// How the numbers adjacent to a symbol are combined into a single value.
+pub enum Aggregate {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregate {
+    /// Combines `numbers` into a single value; the maximum of no numbers is 0.
+    pub fn apply(&self, numbers: &[usize]) -> usize {
+        match self {
+            Aggregate::Product => numbers.iter().product(),
+            Aggregate::Sum => numbers.iter().sum(),
+            Aggregate::Max => numbers.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+impl FromStr for Aggregate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Aggregate::Product),
+            "sum" => Ok(Aggregate::Sum),
+            "max" => Ok(Aggregate::Max),
+            _ => Err(format!("invalid aggregate: {s:?}")),
+        }
+    }
+}
+
+/// Selects symbols by kind and number of adjacent spans; an empty `symbols` set accepts any symbol.
+pub struct Query {
+    pub symbols: Vec<char>,
+    pub arity: Arity,
+    pub aggregate: Aggregate,
+}
+
+/// A symbol accepted by a [`Query`] with its adjacent numbers and their aggregate.
+pub struct Match {
+    pub pos: Pos,
+    pub symbol: char,
+    pub numbers: Vec<usize>,
+    pub value: usize,
+}
+
+impl Schematic {
+    /// Returns every `*` touching exactly two spans together with its gear ratio.
+    pub fn gears(&self) -> Vec<Match> {
+        self.query(&Query { symbols: vec!['*'], arity: Arity::Exactly(2), aggregate: Aggregate::Product })
+    }
+
+    /// Answers `query`, returning the matching symbols in reading order.
+    pub fn query(&self, query: &Query) -> Vec<Match> {
+        self.symbols(|c| query.symbols.is_empty() || query.symbols.contains(&c))
+            .filter(|(_, _, spans)| query.arity.accepts(spans.len()))
+            .map(|(pos, symbol, spans)| {
+                let numbers: Vec<usize> = spans.iter().map(|span| span.value()).collect();
+                let value = query.aggregate.apply(&numbers);
+                Match { pos, symbol, numbers, value }
+            })
+            .collect()
+    }
+}
+
+/// Sums all part numbers.
+pub fn first(schematic: &Schematic) -> usize {
+    schematic.parts()
+        .iter()
+        .map(|span| span.value())
+        .sum()
+}
+
+/// Sums the ratios of all gears.
+pub fn second(schematic: &Schematic) -> usize {
+    schematic.gears()
+        .iter()
+        .inspect(|gear| common::debug!("gear at {}: {:?} -> {}", gear.pos, gear.numbers, gear.value))
+        .map(|gear| gear.value)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schematic(text: &str) -> Schematic {
+        Schematic::new(text.parse().unwrap())
+    }
+
+    fn values(spans: &[&Span]) -> Vec<usize> {
+        spans.iter().map(|span| span.value()).collect()
+    }
+
+    #[test]
+    fn numbers_touching_the_borders() {
+        let schematic = schematic("1.\n*2");
+        assert_eq!(values(&schematic.parts()), [1, 2]);
+        assert_eq!(first(&schematic), 3);
+        assert_eq!(second(&schematic), 2);
+    }
+
+    #[test]
+    fn span_ending_one_column_before_the_right_edge() {
+        let schematic = schematic("..12.\n....*\n7....");
+        assert_eq!(schematic.spans()[0].cells().collect::<Vec<_>>(), [Pos::new(2, 0), Pos::new(3, 0)]);
+        assert_eq!(values(&schematic.parts()), [12]);
+        assert_eq!(first(&schematic), 12);
+    }
+
+    #[test]
+    fn diagonal_symbol_below_the_last_digit() {
+        let schematic = schematic("12.\n..*");
+        assert_eq!(first(&schematic), 12);
+        assert!(schematic.gears().is_empty());
+        assert_eq!(second(&schematic), 0);
+    }
+
+    #[test]
+    fn zero_is_a_part_number() {
+        let schematic = schematic("0*5");
+        assert_eq!(values(&schematic.parts()), [0, 5]);
+        assert_eq!(first(&schematic), 5);
+        let gears = schematic.gears();
+        assert_eq!(gears.len(), 1);
+        assert_eq!(gears[0].numbers, [0, 5]);
+        assert_eq!(second(&schematic), 0);
+    }
+
+    #[test]
+    fn number_shared_by_two_gears() {
+        let schematic = schematic("3.4\n*2*");
+        let gears: Vec<(Pos, Vec<usize>, usize)> = schematic.gears().into_iter()
+            .map(|gear| (gear.pos, gear.numbers, gear.value))
+            .collect();
+        assert_eq!(gears, [(Pos::new(0, 1), vec![3, 2], 6), (Pos::new(2, 1), vec![4, 2], 8)]);
+        assert_eq!(first(&schematic), 9);
+        assert_eq!(second(&schematic), 14);
+    }
+
+    #[test]
+    fn report_lists_counted_and_ignored_spans() {
+        let schematic = schematic("12.\n..*\n5..");
+        assert_eq!(schematic.report(), "\
+            12 at (0, 0): counted, next to * at (2, 1)\n\
+            5 at (0, 2): ignored, no adjacent symbol\n");
+    }
+}
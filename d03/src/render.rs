@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use common::grid::Pos;
+
+use crate::{is_symbol, Schematic};
+
+/// What a single schematic cell is rendered as.
+#[derive(Copy, Clone, PartialEq)]
+enum Cell {
+    Blank,
+    Counted,
+    Ignored,
+    Symbol,
+    Gear,
+}
+
+impl Cell {
+    fn ansi(self) -> &'static str {
+        match self {
+            Cell::Blank => "\x1b[0;2m",
+            Cell::Counted => "\x1b[0;1;32m",
+            Cell::Ignored => "\x1b[0;31m",
+            Cell::Symbol => "\x1b[0;1;36m",
+            Cell::Gear => "\x1b[0;1;33m",
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Cell::Blank => "blank",
+            Cell::Counted => "counted",
+            Cell::Ignored => "ignored",
+            Cell::Symbol => "symbol",
+            Cell::Gear => "gear",
+        }
+    }
+}
+
+/// A classified schematic row with the gear annotations printed after it.
+struct Row {
+    cells: Vec<(char, Cell)>,
+    notes: Vec<String>,
+    /// `a * b = ratio` of every gear on the row, by column.
+    ratios: HashMap<usize, String>,
+}
+
+/// Classifies every cell row by row and collects the gear annotations of each row.
+fn classify(schematic: &Schematic) -> Vec<Row> {
+    let mut cells: HashMap<Pos, Cell> = HashMap::new();
+    for span in schematic.spans.iter() {
+        let cell = if span.symbols.is_empty() { Cell::Ignored } else { Cell::Counted };
+        cells.extend(span.cells().map(|pos| (pos, cell)));
+    }
+
+    let mut notes: Vec<Vec<String>> = vec![Vec::new(); schematic.map.height()];
+    let mut ratios: Vec<HashMap<usize, String>> = vec![HashMap::new(); schematic.map.height()];
+    for gear in schematic.gears() {
+        cells.insert(gear.pos, Cell::Gear);
+        let factors: Vec<String> = gear.numbers.iter().map(usize::to_string).collect();
+        let ratio = format!("{} = {}", factors.join(" * "), gear.value);
+        notes[gear.pos.y].push(format!("gear at {} = {ratio}", gear.pos));
+        ratios[gear.pos.y].insert(gear.pos.x, ratio);
+    }
+
+    schematic.map.rows()
+        .zip(notes.into_iter().zip(ratios))
+        .enumerate()
+        .map(|(y, (row, (notes, ratios)))| Row {
+            cells: row.iter()
+                .enumerate()
+                .map(|(x, &c)| (c, cells.get(&Pos::new(x, y)).copied().unwrap_or(match is_symbol(c) {
+                    true => Cell::Symbol,
+                    false => Cell::Blank,
+                })))
+                .collect(),
+            notes,
+            ratios,
+        })
+        .collect()
+}
+
+/// Reprints the schematic with ANSI colours: counted parts green, ignored numbers red, symbols
+/// cyan and gears yellow, followed by the ratio of each gear on its row.
+pub fn ansi(schematic: &Schematic) -> String {
+    let mut out = String::new();
+    for row in classify(schematic) {
+        let mut current: Option<Cell> = None;
+        for (c, cell) in row.cells {
+            if current != Some(cell) {
+                out.push_str(cell.ansi());
+                current = Some(cell);
+            }
+            out.push(c);
+        }
+        out.push_str("\x1b[0m");
+        if !row.notes.is_empty() {
+            write!(out, "  {}", row.notes.join(", ")).unwrap();
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Renders the schematic as a standalone HTML page with the same highlighting as [`ansi`];
+/// hovering a gear shows its ratio.
+pub fn html(schematic: &Schematic) -> String {
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<style>\n",
+        "pre { font-family: monospace; }\n",
+        ".blank { color: #bbb; }\n",
+        ".counted { color: #080; font-weight: bold; }\n",
+        ".ignored { color: #c00; }\n",
+        ".symbol { color: #088; font-weight: bold; }\n",
+        ".gear { background: #fd0; font-weight: bold; }\n",
+        ".note { color: #666; }\n",
+        "</style>\n</head>\n<body>\n<pre>\n",
+    ));
+    for row in classify(schematic) {
+        for (x, (c, cell)) in row.cells.into_iter().enumerate() {
+            let c = match c {
+                '<' => "&lt;".to_string(),
+                '>' => "&gt;".to_string(),
+                '&' => "&amp;".to_string(),
+                c => c.to_string(),
+            };
+            match row.ratios.get(&x) {
+                Some(ratio) => write!(out, "<span class=\"{}\" title=\"{ratio}\">{c}</span>", cell.class()),
+                None => write!(out, "<span class=\"{}\">{c}</span>", cell.class()),
+            }.unwrap();
+        }
+        if !row.notes.is_empty() {
+            write!(out, "  <span class=\"note\">{}</span>", row.notes.join(", ")).unwrap();
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
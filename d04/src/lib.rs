@@ -0,0 +1,77 @@
+//! Day 4: scoring scratchcards and counting the copies they win.
+
+use std::fmt;
+use std::str::FromStr;
+
+use common::parse::{self, ParseError};
+
+/// A scratchcard with its winning numbers and the numbers you have.
+pub struct Card {
+    pub id: usize,
+    pub winning_nums: Vec<usize>,
+    pub your_nums: Vec<usize>,
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Card {}: ", self.id)?;
+        for winning_num in self.winning_nums.iter() {
+            write!(f, "{winning_num} ")?;
+        }
+        write!(f, "|")?;
+        for your_num in self.your_nums.iter() {
+            write!(f, " {your_num}")?;
+        }
+        write!(f, "")
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, tail) = parse::id_record(s, "Card")?;
+        let (raw_winning_nums, raw_your_nums) = tail
+            .split_once('|')
+            .ok_or_else(|| ParseError::at(s, tail, "expected '|' between the number lists"))?;
+        let winning_nums = parse::numbers(raw_winning_nums)
+            .map_err(|err| err.inside(s, raw_winning_nums))?;
+        let your_nums = parse::numbers(raw_your_nums)
+            .map_err(|err| err.inside(s, raw_your_nums))?;
+        Ok(Card { id, winning_nums, your_nums })
+    }
+}
+
+/// Sums the points of all cards, doubling for every match after the first.
+pub fn first(cards: &[Card]) -> usize {
+    cards.iter()
+        .map(count_winning_nums)
+        .filter_map(|wins| match wins {
+                0 => None,
+                _ => Some(usize::pow(2, (wins - 1) as u32))
+            })
+        .sum()
+}
+
+/// Number of your numbers that are winning numbers.
+pub fn count_winning_nums(card: &Card) -> usize {
+    card.your_nums.iter()
+        .filter(|your_num| card.winning_nums.contains(your_num))
+        .count()
+}
+
+/// Counts all cards including the copies won; `cards` must be ordered by id starting at 1.
+pub fn second(cards: &[Card]) -> usize {
+    cards.iter()
+        .map(|card| recursively_count_cards(cards, card.id))
+        .sum::<usize>()
+}
+
+fn recursively_count_cards(cards: &[Card], card_id: usize) -> usize {
+    1 + match count_winning_nums(&cards[card_id - 1]) {
+        0 => 0,
+        matches => (1..matches + 1)
+            .map(|offset: usize| recursively_count_cards(cards, card_id + offset))
+            .sum::<usize>()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,163 @@
+//! Day 5: mapping seeds through the almanac's chain of range maps.
+
+use std::fmt;
+use std::str::FromStr;
+
+use common::interval::{Interval, IntervalSet, Mapping};
+use common::parse::{self, ParseError};
+
+/// The seeds to plant and the maps leading from seed to location.
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    /// The seeds read as start/length pairs instead.
+    pub seed_ranges: IntervalSet,
+    pub seed_to_soil: Map,
+    pub soil_to_fertilizer: Map,
+    pub fertilizer_to_water: Map,
+    pub water_to_light: Map,
+    pub light_to_temperature: Map,
+    pub temperature_to_humidity: Map,
+    pub humidity_to_location: Map,
+}
+
+/// Piecewise mapping from one category to the next; values outside every range map to themselves.
+///
+/// Written as `<destination start> <source start> <length>` lines.
+pub struct Map {
+    pub mapping: Mapping,
+}
+
+impl fmt::Display for Almanac {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "seeds:")?;
+        for seed in self.seeds.iter() {
+            write!(f, " {seed}")?
+        }
+        write!(f, "\n\nseed-to-soil map:\n{}", self.seed_to_soil)?;
+        write!(f, "\nsoil-to-fertilizer map:\n{}", self.soil_to_fertilizer)?;
+        write!(f, "\nfertilizer-to-water map:\n{}", self.fertilizer_to_water)?;
+        write!(f, "\nwater-to-light map:\n{}", self.water_to_light)?;
+        write!(f, "\nlight-to-temperature map:\n{}", self.light_to_temperature)?;
+        write!(f, "\ntemperature-to-humidity map:\n{}", self.temperature_to_humidity)?;
+        write!(f, "\nhumidity-to-location map:\n{}", self.humidity_to_location)
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let sections = parse::labelled_sections(s)?;
+        let (header, raw_seeds) = sections.first()
+            .ok_or_else(|| ParseError::within(s, s, "expected a \"seeds:\" line"))?;
+        if *header != "seeds" {
+            return Err(ParseError::within(s, header, "expected a \"seeds:\" line"));
+        }
+        let seeds: Vec<i64> = parse::numbers(raw_seeds)
+            .map_err(|err| err.inside(s, raw_seeds).in_text(s, raw_seeds))?;
+        if seeds.is_empty() {
+            return Err(ParseError::within(s, raw_seeds, "expected at least one seed"));
+        }
+        if !seeds.len().is_multiple_of(2) {
+            return Err(ParseError::within(s, raw_seeds, "seeds have to come in start/length pairs"));
+        }
+        let seed_ranges: IntervalSet = seeds.chunks(2)
+            .map(|chunk| Interval::with_len(chunk[0], chunk[1]))
+            .collect();
+        let map = |name: &str| -> Result<Map, ParseError> {
+            let (_, body) = sections[1..].iter()
+                .find(|(header, _)| header.trim_end_matches(" map") == name)
+                .ok_or_else(|| ParseError::within(s, &s[s.len()..], format!("missing {name} map")))?;
+            let mapping = body.lines()
+                .map(|line| parse_range(line).map_err(|err| err.in_text(s, line)))
+                .collect::<Result<_, _>>()?;
+            Ok(Map { mapping })
+        };
+        Ok(Almanac { seeds
+            , seed_ranges
+            , seed_to_soil: map("seed-to-soil")?
+            , soil_to_fertilizer: map("soil-to-fertilizer")?
+            , fertilizer_to_water: map("fertilizer-to-water")?
+            , water_to_light: map("water-to-light")?
+            , light_to_temperature: map("light-to-temperature")?
+            , temperature_to_humidity: map("temperature-to-humidity")?
+            , humidity_to_location: map("humidity-to-location")?
+        })
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (source, offset) in self.mapping.pieces() {
+            writeln!(f, "{} {} {}", source.start + offset, source.start, source.len())?
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Map {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Map { mapping:
+            s.lines()
+                .enumerate()
+                .map(|(no, line)| parse_range(line).map_err(|err| err.at_line(no + 1)))
+                .collect::<Result<_, _>>()?
+        })
+    }
+}
+
+/// Reads a `<destination start> <source start> <length>` line as a source interval and offset.
+fn parse_range(line: &str) -> Result<(Interval, i64), ParseError> {
+    let [dst_start, src_start, length] = parse::exact_numbers(line)?;
+    Ok((Interval::with_len(src_start, length), dst_start - src_start))
+}
+
+impl Map {
+    /// Maps a source value to its destination.
+    pub fn map(&self, val: i64) -> i64 {
+        self.mapping.map(val)
+    }
+
+    /// Maps a whole set of source values to their destinations.
+    pub fn map_set(&self, vals: &IntervalSet) -> IntervalSet {
+        self.mapping.map_set(vals)
+    }
+}
+
+impl Almanac {
+    /// The maps in order, from seed to location.
+    pub fn maps(&self) -> [&Map; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
+    }
+}
+
+/// Lowest location of any listed seed.
+pub fn first(almanac: &Almanac) -> i64 {
+    almanac.seeds.iter()
+        .map(|&seed| almanac.seed_to_soil.map(seed))
+        .map(|soil| almanac.soil_to_fertilizer.map(soil))
+        .map(|fertilizer| almanac.fertilizer_to_water.map(fertilizer))
+        .map(|water| almanac.water_to_light.map(water))
+        .map(|light| almanac.light_to_temperature.map(light))
+        .map(|temperature| almanac.temperature_to_humidity.map(temperature))
+        .map(|humidity| almanac.humidity_to_location.map(humidity))
+        .fold(i64::MAX, i64::min)
+}
+
+/// Lowest location of any seed in the seed ranges, mapping whole ranges at once; `None` if all
+/// ranges are empty.
+pub fn second(almanac: &Almanac) -> Option<i64> {
+    almanac.maps().iter()
+        .fold(almanac.seed_ranges.clone(), |vals, map| map.map_set(&vals))
+        .min()
+}
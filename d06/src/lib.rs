@@ -0,0 +1,37 @@
+//! Day 6: counting the button hold times that win a boat race.
+
+use common::math;
+
+/// A race lasting `time` milliseconds whose record is `distance` millimetres.
+pub struct Race {
+    pub time: usize,
+    pub distance: usize,
+}
+
+impl Race {
+    /// Number of hold times beating the record, counted in closed form.
+    pub fn winning_holds(&self) -> usize {
+        // hold * (time - hold) > distance holds strictly between the roots
+        // (time ± sqrt(time² - 4 * distance)) / 2, symmetrically around time / 2
+        let (time, distance) = (self.time as u128, self.distance as u128);
+        let Some(discriminant) = (time * time).checked_sub(4 * distance) else {
+            return 0;
+        };
+        // the floored root puts `low` at most one step before the first winning hold time
+        let mut low = (time - math::isqrt(discriminant)) / 2;
+        while low <= time / 2 && low * (time - low) <= distance {
+            low += 1;
+        }
+        match low <= time / 2 {
+            true => (time - 2 * low + 1) as usize,
+            false => 0,
+        }
+    }
+}
+
+/// Multiplies the number of winning hold times over all races.
+pub fn calc(races: &[Race]) -> usize {
+    races.iter()
+        .map(Race::winning_holds)
+        .product()
+}
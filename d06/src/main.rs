@@ -1,4 +1,14 @@
+use std::env;
+
+use common::log;
+use common::report::Report;
+use d06::{calc, Race};
+
 fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    log::from_args(&mut args);
+    let report = Report::from_args(6, &mut args);
+
     {
         let races: Vec<Race> = vec![
             Race { time: 61, distance: 430 },
@@ -6,31 +16,12 @@ fn main() {
             Race { time: 75, distance: 1307 },
             Race { time: 71, distance: 1150 },
         ];
-        println!("first = {}", calc(&races));
+        report.answer("first", || calc(&races));
     }
     {
         let races: Vec<Race> = vec![
             Race { time: 61677571, distance: 430103613071150 },
         ];
-        println!("second = {}", calc(&races));
+        report.answer("second", || calc(&races));
     }
 }
-
-struct Race {
-    time: usize,
-    distance: usize,
-}
-
-fn distance_per_accel(time_accel: usize, time_total: usize) -> usize {
-    time_accel * (time_total - time_accel)
-}
-
-fn calc(races: &Vec<Race>) -> usize {
-    races.iter()
-        .map(|race| (0..race.time)
-            .map(|accel| distance_per_accel(accel, race.time))
-            .filter(|&distance| distance > race.distance)
-            .count()
-        )
-        .product()
-}
\ No newline at end of file
@@ -0,0 +1,241 @@
+//! Day 7: ranking Camel Cards hands, with and without jokers.
+
+use std::fmt;
+use std::str::FromStr;
+use std::cmp::Ordering;
+use std::cmp::Ordering::{Equal, Greater, Less};
+use common::parse::ParseError;
+
+use crate::Type::{FullHouse, HighCard, OnePair, Quadruple, Quintuple, Triple, TwoPairs};
+
+/// A hand of five cards and the bid placed on it.
+pub struct Player {
+    pub hand: String,
+    pub bid: usize,
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "hand: {}, bid: {}", self.hand, self.bid)
+    }
+}
+
+impl FromStr for Player {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (raw_hand, raw_bid) = s.trim().split_once(char::is_whitespace)
+            .ok_or_else(|| ParseError::at(s, s.trim_start(), "expected a hand and a bid"))?;
+        if let Some((index, card)) = raw_hand.char_indices().find(|&(_, card)| value_of(card) == 0) {
+            return Err(ParseError::at(s, &raw_hand[index..], format!("invalid card {card:?}")));
+        }
+        if raw_hand.chars().count() != 5 {
+            return Err(ParseError::at(s, raw_hand, format!("expected 5 cards, found {}", raw_hand.chars().count())));
+        }
+        let raw_bid = raw_bid.trim();
+        let bid = raw_bid.parse()
+            .map_err(|_| ParseError::at(s, raw_bid, format!("invalid bid {raw_bid:?}")))?;
+        Ok(Player { hand: raw_hand.to_string(), bid })
+    }
+}
+
+/// Strength of a card label when `J` is a jack.
+pub fn value_of(c: char) -> usize {
+    match c {
+        '2' => 1,
+        '3' => 2,
+        '4' => 3,
+        '5' => 4,
+        '6' => 5,
+        '7' => 6,
+        '8' => 7,
+        '9' => 8,
+        'T' => 9,
+        'J' => 10,
+        'Q' => 11,
+        'K' => 12,
+        'A' => 13,
+        _ => 0,
+    }
+}
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq,Ord,PartialOrd)]
+/// Kind of a hand, ordered from weakest to strongest.
+pub enum Type {
+    HighCard,
+    OnePair,
+    TwoPairs,
+    Triple,
+    FullHouse,
+    Quadruple,
+    Quintuple,
+}
+
+/// Orders hands by kind, then card by card, when `J` is a jack.
+pub fn cmp_hands(a: &str, b: &str) -> Ordering {
+    match cmp_types(get_type(a), get_type(b)) {
+        Ordering::Equal => cmp_labels(a, b),
+        other => other,
+    }
+}
+
+fn cmp_labels(a: &str, b: &str) -> Ordering {
+    a.chars().map(value_of).collect::<Vec<_>>()
+        .cmp(&b.chars().map(value_of).collect::<Vec<_>>())
+}
+
+fn cmp_types(a: Type, b: Type) -> Ordering {
+    if a < b {
+        Less
+    } else if a > b {
+        Greater
+    } else {
+        Equal
+    }
+}
+
+/// Kind of `hand` when `J` is a jack.
+pub fn get_type(hand: &str) -> Type {
+    let mut map = vec![0usize; 14];
+    hand.chars()
+        .map(value_of)
+        .for_each(|label| map[label] += 1);
+
+    if map.contains(&5) {
+        Quintuple
+    } else if map.contains(&4) {
+        Quadruple
+    } else if map.contains(&3) && map.contains(&2) {
+        FullHouse
+    } else if map.contains(&3) {
+        Triple
+    } else if contains_n_times_n(2, 2, &map) {
+        TwoPairs
+    } else if map.contains(&2) {
+        OnePair
+    } else {
+        HighCard
+    }
+}
+
+fn contains_n_times_n(count: usize, times: usize, map: &[usize]) -> bool {
+    map.iter().filter(|&val| *val == count).count() == times
+}
+
+/// Total winnings with `J` as jacks; sorts `players` by rank.
+pub fn first(players: &mut [Player]) -> usize {
+    players
+        .sort_by(|a, b|
+            cmp_hands(&a.hand, &b.hand));
+    players
+        .iter()
+        .enumerate()
+        .map(|(rank, player)| (rank + 1) * player.bid)
+        .sum()
+}
+
+/// Strength of a card label when `J` is a joker.
+pub fn second_value_of(c: char) -> usize {
+    match c {
+        '2' => 2,
+        '3' => 3,
+        '4' => 4,
+        '5' => 5,
+        '6' => 6,
+        '7' => 7,
+        '8' => 8,
+        '9' => 9,
+        'T' => 10,
+        'J' => 1,
+        'Q' => 11,
+        'K' => 12,
+        'A' => 13,
+        _ => 0,
+    }
+}
+
+/// Orders hands by kind, then card by card, when `J` is a joker.
+pub fn second_cmp_hands(a: &str, b: &str) -> Ordering {
+    match cmp_types(second_get_type(a), second_get_type(b)) {
+        Equal => second_cmp_labels(a, b),
+        other => other,
+    }
+}
+
+fn second_cmp_labels(a: &str, b: &str) -> Ordering {
+    a.chars().map(second_value_of).collect::<Vec<_>>()
+        .cmp(&b.chars().map(second_value_of).collect::<Vec<_>>())
+}
+
+/// Kind of `hand` when each `J` joins whichever label makes the hand strongest.
+pub fn second_get_type(hand: &str) -> Type {
+    let mut map = vec![0usize; 14];
+    hand.chars()
+        .map(second_value_of)
+        .for_each(|label| map[label] += 1);
+    let jokers = map[second_value_of('J')];
+
+    let kind = if map.contains(&(5 - jokers)) {
+        Quintuple
+    } else if map.contains(&4)
+        || jokers == 3
+        || jokers == 2 && contains_n_times_n(2, 2, &map)
+        || jokers == 1 && map.contains(&3)
+    {
+        Quadruple
+    } else if map.contains(&3) && map.contains(&2)
+        || jokers == 2 && contains_n_times_n(2, 2, &map)
+        || jokers == 1 && (contains_n_times_n(2, 2, &map) || contains_n_times_n(3, 1, &map))
+    {
+        FullHouse
+    } else if map.contains(&(3 - jokers)) {
+        Triple
+    } else if contains_n_times_n(2, 2, &map)
+        || jokers == 2
+        || jokers == 1 && (map.contains(&2))
+    {
+        TwoPairs
+    } else if map.contains(&(2 - jokers)) {
+        OnePair
+    } else {
+        HighCard
+    };
+    common::debug!("{hand}: J = {jokers} -> {kind:?}");
+    kind
+}
+
+/// Total winnings with `J` as jokers; sorts `players` by rank.
+pub fn second(players: &mut [Player]) -> usize {
+    players
+        .sort_by(|a, b|
+            second_cmp_hands(&a.hand, &b.hand));
+
+    players
+        .iter()
+        .enumerate()
+        .map(|(rank, player)| (rank + 1) * player.bid)
+        .sum()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(s: &str) -> (usize, String) {
+        let err = s.parse::<Player>().err().unwrap();
+        (err.column, err.message)
+    }
+
+    #[test]
+    fn parses_hand_and_bid() {
+        let player: Player = "T55J5  684".parse().unwrap();
+        assert_eq!((player.hand.as_str(), player.bid), ("T55J5", 684));
+    }
+
+    #[test]
+    fn rejects_malformed_players() {
+        assert_eq!(error("AKQJT"), (1, "expected a hand and a bid".to_string()));
+        assert_eq!(error("AKQJT x1"), (7, "invalid bid \"x1\"".to_string()));
+        assert_eq!(error("AK1JT 5"), (3, "invalid card '1'".to_string()));
+        assert_eq!(error("AKQJ 5"), (1, "expected 5 cards, found 4".to_string()));
+    }
+}
@@ -0,0 +1,71 @@
+//! Exporting the network for other tools: Graphviz and JSON.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use common::report::json_string as quoted;
+
+use crate::Map;
+use crate::select::Selector;
+
+/// Renders the network as a Graphviz digraph.
+///
+/// Nodes accepted by `starts` are filled green, those accepted by `ends` red, and the edges taken
+/// along any of the `paths` are drawn bold.
+pub fn to_dot(map: &Map, starts: &Selector, ends: &Selector, paths: &[Vec<usize>]) -> String {
+    let taken: HashSet<(usize, usize)> = paths.iter()
+        .flat_map(|path| path.windows(2))
+        .map(|step| (step[0], step[1]))
+        .collect();
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph network {{").unwrap();
+    writeln!(dot, "    node [shape=circle];").unwrap();
+    for id in 0..map.len() {
+        let name = map.name(id);
+        let style = if starts.matches(name) {
+            ", style=filled, fillcolor=palegreen"
+        } else if ends.matches(name) {
+            ", style=filled, fillcolor=lightcoral"
+        } else {
+            ""
+        };
+        writeln!(dot, "    n{id} [label={}{style}];", quoted(name)).unwrap();
+    }
+    for id in 0..map.len() {
+        let (left, right) = map.edges(id);
+        let edges: Vec<(usize, &str)> = if left == right {
+            vec![(left, "LR")]
+        } else {
+            vec![(left, "L"), (right, "R")]
+        };
+        for (target, label) in edges {
+            let style = if taken.contains(&(id, target)) { ", color=blue, penwidth=2" } else { "" };
+            writeln!(dot, "    n{id} -> n{target} [label={label}{style}];").unwrap();
+        }
+    }
+    writeln!(dot, "}}").unwrap();
+    dot
+}
+
+/// Renders the network as a JSON adjacency list together with the nodes visited along `paths`.
+pub fn to_json(map: &Map, starts: &Selector, ends: &Selector, paths: &[Vec<usize>]) -> String {
+    let nodes: Vec<String> = (0..map.len())
+        .map(|id| {
+            let (left, right) = map.edges(id);
+            let name = map.name(id);
+            format!("{{\"id\": {id}, \"name\": {}, \"left\": {left}, \"right\": {right}, \"start\": {}, \"end\": {}}}",
+                    quoted(name), starts.matches(name), ends.matches(name))
+        })
+        .collect();
+    let paths: Vec<String> = paths.iter()
+        .map(|path| path.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", "))
+        .map(|path| format!("[{path}]"))
+        .collect();
+    format!("{{\"instructions\": {}, \"nodes\": [{}], \"paths\": [{}]}}",
+            quoted(&map.instructions), nodes.join(", "), paths.join(", "))
+}
+
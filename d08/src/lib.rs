@@ -0,0 +1,398 @@
+//! Day 8: walking the desert network, alone and as a group of ghosts.
+
+use std::fmt;
+use std::str::FromStr;
+
+use common::graph::{self, Graph, Interner};
+use common::input;
+use common::math;
+use common::parse::{self, ParseError};
+
+pub mod export;
+pub mod select;
+
+use select::Selector;
+
+/// Left/right instructions and the network they are followed through.
+///
+/// Nodes are referred to by dense ids; [`Map::id`] and [`Map::name`] translate from and to names.
+pub struct Map {
+    instructions: String,
+    nodes: Interner,
+    /// Every node has exactly two edges, left before right.
+    network: Graph,
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.instructions)?;
+        writeln!(f)?;
+        for id in 0..self.len() {
+            let (left, right) = self.edges(id);
+            writeln!(f, "{} = ({}, {})", self.nodes.name(id), self.nodes.name(left), self.nodes.name(right))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Map {
+    type Err = MapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (instructions, raw_map) = match input::sections(s)[..] {
+            [instructions, raw_map] => (instructions, raw_map),
+            _ => return Err(ParseError::within(s, s, "expected the instructions and the network, separated by a blank line").into()),
+        };
+        if let Some(c) = instructions.chars().find(|&c| c != 'L' && c != 'R') {
+            return Err(MapError::InvalidInstruction(c));
+        }
+
+        let mut nodes = Interner::new();
+        let mut network: Vec<Option<(usize, usize)>> = Vec::new();
+        for line in raw_map.lines() {
+            let (node, left, right) = parse::key_tuple(line).map_err(|err| err.in_text(s, line))?;
+            let id = nodes.intern(node);
+            let edges = (nodes.intern(left), nodes.intern(right));
+            network.resize(nodes.len(), None);
+            if network[id].replace(edges).is_some() {
+                return Err(MapError::DuplicateNode(node.to_string()));
+            }
+        }
+        network.resize(nodes.len(), None);
+
+        let mut adjacency = Graph::with_len(nodes.len());
+        for (id, edges) in network.into_iter().enumerate() {
+            let (left, right) = edges.ok_or_else(|| MapError::UndefinedNode(nodes.name(id).to_string()))?;
+            adjacency.add_edge(id, left);
+            adjacency.add_edge(id, right);
+        }
+
+        Ok(Map { instructions: instructions.to_string(), nodes, network: adjacency })
+    }
+}
+
+/// Reasons a network fails to parse or a walk fails to arrive.
+#[derive(Debug)]
+pub enum MapError {
+    Syntax(ParseError),
+    InvalidInstruction(char),
+    DuplicateNode(String),
+    UndefinedNode(String),
+    /// The node a walk should start from does not exist.
+    MissingStart(String),
+    /// The walk from `from` returned to `node` at instruction `position` after `steps` steps
+    /// (first seen after `since` steps) without ever reaching `to`.
+    Loop { from: String, to: String, node: String, position: usize, since: usize, steps: usize },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Syntax(err) => write!(f, "{err}"),
+            MapError::InvalidInstruction(c) => write!(f, "invalid instruction: {c:?}"),
+            MapError::DuplicateNode(node) => write!(f, "node {node} is defined twice"),
+            MapError::UndefinedNode(node) => write!(f, "node {node} is referenced but never defined"),
+            MapError::MissingStart(node) => write!(f, "start node {node} does not exist"),
+            MapError::Loop { from, to, node, position, since, steps } => write!(f,
+                "{to} is unreachable from {from}: walk revisits {node} at instruction {position} \
+                after {steps} steps (first seen after {since} steps)"),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<ParseError> for MapError {
+    fn from(err: ParseError) -> Self {
+        MapError::Syntax(err)
+    }
+}
+
+impl Map {
+    /// The left/right instructions, repeated as often as needed.
+    pub fn instructions(&self) -> &str {
+        &self.instructions
+    }
+
+    /// Number of nodes in the network.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the network has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Id of the node called `name`, if there is one.
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.nodes.id(name)
+    }
+
+    /// Name of the node with id `id`.
+    pub fn name(&self, id: usize) -> &str {
+        self.nodes.name(id)
+    }
+
+    /// Left and right successors of `id`.
+    pub fn edges(&self, id: usize) -> (usize, usize) {
+        match self.network.edges(id) {
+            &[(left, _), (right, _)] => (left, right),
+            _ => unreachable!("every node has a left and a right edge"),
+        }
+    }
+
+    /// Returns the ids of all nodes accepted by `selector`.
+    pub fn select(&self, selector: &Selector) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&node| selector.matches(self.nodes.name(node)))
+            .collect()
+    }
+
+    /// Number of steps from `start` to the first `goal` node.
+    pub fn steps_from_to(&self, start: usize, goal: &Selector) -> Result<usize, MapError> {
+        self.path_from_to(start, goal).map(|path| path.len() - 1)
+    }
+
+    /// Walks from every node accepted by `starts` to the first node accepted by `goal`.
+    pub fn steps_from_each(&self, starts: &Selector, goal: &Selector) -> Vec<(usize, Result<usize, MapError>)> {
+        self.select(starts).into_iter()
+            .map(|start| (start, self.steps_from_to(start, goal)))
+            .collect()
+    }
+
+    /// Returns the first step count at which walks from all `starts` are on a `goal` node at once.
+    pub fn earliest_arrival(&self, starts: &Selector, goal: &Selector) -> Option<usize> {
+        let cycles: Vec<Cycle> = self.select(starts).into_iter()
+            .map(|start| self.cycle_from(start, goal))
+            .collect();
+        earliest_common_hit(&cycles)
+    }
+
+    /// Returns every node visited on the walk from `start` to the first `goal` node, both included.
+    ///
+    /// Stops as soon as the goal is reached; only walks that never reach it are followed until
+    /// a (node, instruction index) state repeats.
+    pub fn path_from_to(&self, start: usize, goal: &Selector) -> Result<Vec<usize>, MapError> {
+        let instructions: Vec<char> = self.instructions.chars().collect();
+        let mut seen: Vec<Option<usize>> = vec![None; self.len() * instructions.len()];
+        let mut path = vec![start];
+        let (mut node, mut position) = (start, 0);
+        while seen[node * instructions.len() + position].is_none() {
+            seen[node * instructions.len() + position] = Some(path.len() - 1);
+            node = self.step(node, instructions[position]);
+            position = (position + 1) % instructions.len();
+            path.push(node);
+            if goal.matches(self.nodes.name(node)) {
+                return Ok(path);
+            }
+        }
+        Err(MapError::Loop {
+            from: self.nodes.name(start).to_string(),
+            to: goal.to_string(),
+            node: self.nodes.name(node).to_string(),
+            position,
+            since: seen[node * instructions.len() + position].unwrap(),
+            steps: path.len() - 1,
+        })
+    }
+
+    /// Follows a single instruction from `node`.
+    pub fn step(&self, node: usize, instruction: char) -> usize {
+        match instruction {
+            'L' => self.edges(node).0,
+            'R' => self.edges(node).1,
+            _ => { panic!("invalid direction") },
+        }
+    }
+
+    /// Walks from `start` over (node, instruction index) states until one repeats.
+    fn walk_from(&self, start: usize) -> graph::Rho<(usize, usize)> {
+        let instructions: Vec<char> = self.instructions.chars().collect();
+        graph::find_cycle((start, 0), |&(node, pos)| {
+            (self.step(node, instructions[pos]), (pos + 1) % instructions.len())
+        })
+    }
+
+    /// Walks from `start` over (node, instruction index) states until one repeats and records
+    /// every step count at which a node accepted by `goal` is reached.
+    pub fn cycle_from(&self, start: usize, goal: &Selector) -> Cycle {
+        let walk = self.walk_from(start);
+        // after `states.len()` steps the walk is back in `states[prefix]`; that is only a new
+        // step count within the first period if the cycle starts right at the start
+        let (pre_hits, offsets) = walk.states.iter()
+            .chain((walk.prefix == 0).then(|| &walk.states[0]))
+            .enumerate()
+            .skip(1)
+            .filter(|&(_, &(node, _))| goal.matches(self.nodes.name(node)))
+            .map(|(count, _)| count)
+            .partition(|&hit| hit < walk.prefix);
+        Cycle { start, prefix: walk.prefix, length: walk.length(), pre_hits, offsets }
+    }
+}
+
+/// Periodic behaviour of a single walk.
+///
+/// After `prefix` steps the walk repeats every `length` steps. End nodes reached before that
+/// are listed in `pre_hits`; those reached within the first period are listed in `offsets`,
+/// each in `prefix..prefix + length`, or in `1..=length` if `prefix` is 0.
+pub struct Cycle {
+    pub start: usize,
+    pub prefix: usize,
+    pub length: usize,
+    pub pre_hits: Vec<usize>,
+    pub offsets: Vec<usize>,
+}
+
+impl Cycle {
+    /// Whether the walk is on an end node after `steps` steps.
+    pub fn hits(&self, steps: usize) -> bool {
+        if steps < self.prefix {
+            return self.pre_hits.contains(&steps);
+        }
+        let phase = (steps - self.prefix) % self.length;
+        self.offsets.iter().any(|&offset| (offset - self.prefix) % self.length == phase)
+    }
+}
+
+/// Returns the earliest step count at which all `cycles` sit on an end node at the same time.
+pub fn earliest_common_hit(cycles: &[Cycle]) -> Option<usize> {
+    let latest = cycles.iter().max_by_key(|cycle| cycle.prefix)?;
+
+    // before every walk is periodic, a common hit has to be a pre-period hit of the slowest one
+    if let Some(&steps) = latest.pre_hits.iter()
+        .find(|&&steps| cycles.iter().all(|cycle| cycle.hits(steps))) {
+        return Some(steps);
+    }
+
+    // afterwards, pick one offset per walk and solve the resulting system of congruences
+    let mut systems: Vec<(u128, u128)> = vec![(0, 1)];
+    for cycle in cycles {
+        systems = systems.iter()
+            .flat_map(|&system| cycle.offsets.iter()
+                .filter_map(move |&offset| math::crt(system, (offset as u128, cycle.length as u128))))
+            .collect();
+    }
+    systems.iter()
+        .map(|&(residue, modulus)| {
+            // a common hit takes at least one step
+            let min = latest.prefix.max(1) as u128;
+            if residue >= min {
+                residue
+            } else {
+                residue + (min - residue).div_ceil(modulus) * modulus
+            }
+        })
+        .min()
+        .map(|steps| steps as usize)
+}
+
+/// Steps from `AAA` to `ZZZ`.
+pub fn first(map: &Map) -> Result<usize, MapError> {
+    let start = map.nodes.id("AAA").ok_or_else(|| MapError::MissingStart("AAA".to_string()))?;
+    map.steps_from_to(start, &Selector::List(vec!["ZZZ".to_string()]))
+}
+
+/// Steps until ghosts starting on every `..A` node are all on `..Z` nodes at once.
+pub fn second(map: &Map) -> Option<usize> {
+    let goal = Selector::Suffix("Z".to_string());
+    let cycles: Vec<Cycle> = map.select(&Selector::Suffix("A".to_string())).into_iter()
+        .map(|start| map.cycle_from(start, &goal))
+        .collect();
+    for cycle in cycles.iter() {
+        common::info!("{}: prefix = {}, cycle = {}, hits = {:?} + {:?}",
+                      map.nodes.name(cycle.start), cycle.prefix, cycle.length, cycle.pre_hits, cycle.offsets);
+    }
+    earliest_common_hit(&cycles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GHOSTS: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    fn selector(s: &str) -> Selector {
+        s.parse().unwrap()
+    }
+
+    fn cycle(prefix: usize, length: usize, pre_hits: &[usize], offsets: &[usize]) -> Cycle {
+        Cycle { start: 0, prefix, length, pre_hits: pre_hits.to_vec(), offsets: offsets.to_vec() }
+    }
+
+    #[test]
+    fn cycle_with_a_tail() {
+        let map: Map = GHOSTS.parse().unwrap();
+        let cycle = map.cycle_from(map.id("22A").unwrap(), &selector("suffix:Z"));
+        assert_eq!((cycle.prefix, cycle.length), (1, 6));
+        assert_eq!(cycle.pre_hits, Vec::<usize>::new());
+        assert_eq!(cycle.offsets, [3, 6]);
+        assert!(cycle.hits(9) && cycle.hits(12) && !cycle.hits(10));
+    }
+
+    #[test]
+    fn cycle_through_the_start_keeps_the_hit_on_returning() {
+        let map: Map = GHOSTS.parse().unwrap();
+        let cycle = map.cycle_from(map.id("11Z").unwrap(), &selector("suffix:Z"));
+        assert_eq!((cycle.prefix, cycle.length), (0, 2));
+        assert_eq!(cycle.offsets, [2]);
+        assert!(cycle.hits(2) && cycle.hits(4) && !cycle.hits(3));
+
+        let map: Map = "L\n\nAZ = (AZ, AZ)\n".parse().unwrap();
+        let cycle = map.cycle_from(0, &Selector::Any);
+        assert_eq!((cycle.prefix, cycle.length, &cycle.offsets[..]), (0, 1, &[1][..]));
+        assert_eq!(earliest_common_hit(&[cycle]), Some(1));
+    }
+
+    #[test]
+    fn ghosts_starting_on_goal_nodes() {
+        let map: Map = GHOSTS.parse().unwrap();
+        assert_eq!(map.earliest_arrival(&selector("suffix:Z"), &selector("suffix:Z")), Some(6));
+        assert_eq!(map.earliest_arrival(&selector("suffix:A"), &selector("suffix:Z")), Some(6));
+        assert_eq!(second(&map), Some(6));
+    }
+
+    #[test]
+    fn common_hit_before_the_cycles() {
+        let cycles = [cycle(5, 4, &[2, 3], &[6]), cycle(1, 2, &[], &[1])];
+        assert_eq!(earliest_common_hit(&cycles), Some(3));
+    }
+
+    #[test]
+    fn common_hit_among_several_offsets() {
+        let cycles = [cycle(0, 5, &[], &[2, 4]), cycle(0, 7, &[], &[3, 6])];
+        assert_eq!(earliest_common_hit(&cycles), Some(17));
+    }
+
+    #[test]
+    fn common_hit_with_cycle_lengths_sharing_a_factor() {
+        let cycles = [cycle(0, 4, &[], &[2]), cycle(0, 6, &[], &[4])];
+        assert_eq!(earliest_common_hit(&cycles), Some(10));
+        let cycles = [cycle(3, 4, &[], &[3]), cycle(0, 6, &[], &[3])];
+        assert_eq!(earliest_common_hit(&cycles), Some(3));
+    }
+
+    #[test]
+    fn common_hit_respects_the_longest_tail() {
+        let cycles = [cycle(9, 2, &[], &[10]), cycle(0, 2, &[], &[2])];
+        assert_eq!(earliest_common_hit(&cycles), Some(10));
+    }
+
+    #[test]
+    fn no_common_hit() {
+        let cycles = [cycle(0, 4, &[], &[1]), cycle(0, 6, &[], &[2, 4])];
+        assert_eq!(earliest_common_hit(&cycles), None);
+        assert_eq!(earliest_common_hit(&[]), None);
+        assert_eq!(earliest_common_hit(&[cycle(2, 3, &[], &[])]), None);
+    }
+}
@@ -0,0 +1,64 @@
+//! Choosing start and goal nodes by name.
+
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+
+/// Predicate over node names, used to pick the start nodes and goal nodes of a walk.
+///
+/// Parses from `any`, `suffix:<s>`, `prefix:<s>`, `regex:<pattern>` or `list:<name>,<name>,…`.
+pub enum Selector {
+    Any,
+    Suffix(String),
+    Prefix(String),
+    Regex(Regex),
+    List(Vec<String>),
+}
+
+impl Selector {
+    /// Whether the node called `name` is selected.
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Selector::Any => true,
+            Selector::Suffix(suffix) => name.ends_with(suffix.as_str()),
+            Selector::Prefix(prefix) => name.starts_with(prefix.as_str()),
+            Selector::Regex(regex) => regex.is_match(name),
+            Selector::List(names) => names.iter().any(|n| n == name),
+        }
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Selector::Any => write!(f, "any"),
+            Selector::Suffix(suffix) => write!(f, "suffix:{suffix}"),
+            Selector::Prefix(prefix) => write!(f, "prefix:{prefix}"),
+            Selector::Regex(regex) => write!(f, "regex:{regex}"),
+            Selector::List(names) => write!(f, "list:{}", names.join(",")),
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "any" {
+            return Ok(Selector::Any);
+        }
+        match s.split_once(':') {
+            Some(("suffix", suffix)) => Ok(Selector::Suffix(suffix.to_string())),
+            Some(("prefix", prefix)) => Ok(Selector::Prefix(prefix.to_string())),
+            Some(("regex", pattern)) => Regex::new(pattern)
+                .map(Selector::Regex)
+                .map_err(|err| err.to_string()),
+            Some(("list", names)) => Ok(Selector::List(names
+                .split(',')
+                .map(str::to_string)
+                .collect())),
+            _ => Err(format!("invalid selector: {s:?}")),
+        }
+    }
+}